@@ -1,11 +1,12 @@
 use cosmwasm_schema::write_api;
 
-use otcer_pkg::otcer::msgs::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use otcer_pkg::otcer::msgs::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 
 fn main() {
     write_api! {
         instantiate: InstantiateMsg,
         execute: ExecuteMsg,
-        query: QueryMsg
+        query: QueryMsg,
+        migrate: MigrateMsg
     }
 }
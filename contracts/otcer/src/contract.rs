@@ -1,18 +1,37 @@
-use cosmwasm_std::{entry_point, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use cosmwasm_std::{
+    entry_point, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdError,
+    StdResult,
+};
 
+use cw2::set_contract_version;
 use otcer_pkg::otcer::{
-    definitions::Config,
+    definitions::{Config, ExcessFundsPolicy, FeeCollector, HookPolicy},
     msgs::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg},
 };
-use rhaki_cw_plus::traits::{IntoAddr, IntoBinaryResult};
+use rhaki_cw_plus::traits::IntoBinaryResult;
 
 use crate::{
-    execute::{run_cancel_otc, run_claim_otc, run_create_otc, run_execute_otc},
-    query::{qy_position, qy_positions},
+    execute::{
+        run_accept_bid, run_accept_unwind, run_add_hook, run_approve_milestone, run_bid_auction,
+        run_cancel_nonces, run_cancel_otc, run_claim_otc, run_commit_execution, run_create_otc,
+        run_create_ring_trade, run_deposit_ring_leg, run_execute_many, run_execute_otc,
+        run_execute_signed_order, run_place_bid, run_propose_unwind, run_raise_dispute,
+        run_recover_stuck_funds, run_refund_ring_trade, run_remove_hook, run_resolve_dispute,
+        run_settle_auction, run_slash_bond, run_update_config, run_withdraw_bid,
+    },
+    functions::{rebuild_escrow, REPLY_ID_HOOK},
+    query::{
+        qy_bids, qy_claimable, qy_config, qy_contract_info, qy_daily_stats, qy_hooks,
+        qy_nonce_used, qy_position, qy_positions, qy_referral_totals, qy_ring_trade, qy_solvency,
+        qy_stats, qy_vesting_timeline,
+    },
     response::ContractResponse,
-    state::CONFIG,
+    state::{index_legacy_positions, CONFIG, LEGACY_CONFIG},
 };
 
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 #[entry_point]
 pub fn instantiate(
     deps: DepsMut,
@@ -20,17 +39,16 @@ pub fn instantiate(
     _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> ContractResponse {
-    let config = Config::new(
-        deps.as_ref(),
-        msg.owner.clone().into_addr(deps.api)?,
-        msg.fee,
-        msg.fee_collector.into_addr(deps.api)?,
-    )?;
+    let owner = msg.owner.clone();
+
+    let config = Config::new(deps.as_ref(), msg)?;
     CONFIG.save(deps.storage, &config)?;
 
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
     Ok(Response::new()
         .add_attribute("action", "instantiate")
-        .add_attribute("owner", msg.owner))
+        .add_attribute("owner", owner))
 }
 
 #[entry_point]
@@ -38,25 +56,117 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> C
     match msg {
         ExecuteMsg::CreateOtc(msg) => run_create_otc(deps, env, info, msg),
         ExecuteMsg::ExecuteOtc(msg) => run_execute_otc(deps, env, info, msg),
+        ExecuteMsg::ExecuteMany(msg) => run_execute_many(deps, env, info, msg),
+        ExecuteMsg::CreateRingTrade(msg) => run_create_ring_trade(deps, env, info, msg),
+        ExecuteMsg::DepositRingLeg(msg) => run_deposit_ring_leg(deps, env, info, msg),
+        ExecuteMsg::RefundRingTrade(msg) => run_refund_ring_trade(deps, env, info, msg),
+        ExecuteMsg::CommitExecution(msg) => run_commit_execution(deps, env, info, msg),
+        ExecuteMsg::ExecuteSignedOrder(msg) => run_execute_signed_order(deps, env, info, msg),
+        ExecuteMsg::CancelNonces(msg) => run_cancel_nonces(deps, env, info, msg),
         ExecuteMsg::ClaimOtc(msg) => run_claim_otc(deps, env, info, msg),
         ExecuteMsg::CancelOtc(msg) => run_cancel_otc(deps, env, info, msg),
+        ExecuteMsg::SlashBond(msg) => run_slash_bond(deps, env, info, msg),
+        ExecuteMsg::ProposeUnwind(msg) => run_propose_unwind(deps, env, info, msg),
+        ExecuteMsg::AcceptUnwind(msg) => run_accept_unwind(deps, env, info, msg),
+        ExecuteMsg::ApproveMilestone(msg) => run_approve_milestone(deps, env, info, msg),
+        ExecuteMsg::RaiseDispute(msg) => run_raise_dispute(deps, env, info, msg),
+        ExecuteMsg::ResolveDispute(msg) => run_resolve_dispute(deps, env, info, msg),
+        ExecuteMsg::BidAuction(msg) => run_bid_auction(deps, env, info, msg),
+        ExecuteMsg::SettleAuction(msg) => run_settle_auction(deps, env, info, msg),
+        ExecuteMsg::PlaceBid(msg) => run_place_bid(deps, env, info, msg),
+        ExecuteMsg::WithdrawBid(msg) => run_withdraw_bid(deps, env, info, msg),
+        ExecuteMsg::AcceptBid(msg) => run_accept_bid(deps, env, info, msg),
+        ExecuteMsg::UpdateConfig(msg) => run_update_config(deps, env, info, msg),
+        ExecuteMsg::AddHook(msg) => run_add_hook(deps, env, info, msg),
+        ExecuteMsg::RemoveHook(msg) => run_remove_hook(deps, env, info, msg),
+        ExecuteMsg::RecoverStuckFunds(msg) => run_recover_stuck_funds(deps, env, info, msg),
     }
 }
 
 #[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
-        QueryMsg::Position { id } => qy_position(deps, id).into_binary(),
+        QueryMsg::Position { id } => qy_position(deps, env, id).into_binary(),
         QueryMsg::Positions {
             limit,
             start_after,
             filters,
             order,
-        } => qy_positions(deps, start_after, limit, filters, order).into_binary(),
+        } => qy_positions(deps, env, start_after, limit, filters, order).into_binary(),
+        QueryMsg::Bids {
+            id,
+            limit,
+            start_after,
+        } => qy_bids(deps, id, start_after, limit).into_binary(),
+        QueryMsg::Hooks {} => qy_hooks(deps).into_binary(),
+        QueryMsg::ReferralTotals { referrer } => qy_referral_totals(deps, referrer).into_binary(),
+        QueryMsg::Solvency { limit, start_after } => {
+            qy_solvency(deps, env, start_after, limit).into_binary()
+        }
+        QueryMsg::NonceUsed { maker, nonce } => qy_nonce_used(deps, maker, nonce).into_binary(),
+        QueryMsg::RingTrade { id } => qy_ring_trade(deps, id).into_binary(),
+        QueryMsg::Claimable {
+            id,
+            address,
+            at_time,
+        } => qy_claimable(deps, env, id, address, at_time).into_binary(),
+        QueryMsg::VestingTimeline { id } => qy_vesting_timeline(deps, id).into_binary(),
+        QueryMsg::Config {} => qy_config(deps).into_binary(),
+        QueryMsg::ContractInfo {} => qy_contract_info(deps).into_binary(),
+        QueryMsg::Stats {} => qy_stats(deps).into_binary(),
+        QueryMsg::DailyStats { start_after, limit } => {
+            qy_daily_stats(deps, start_after, limit).into_binary()
+        }
+    }
+}
+
+#[entry_point]
+pub fn reply(_deps: DepsMut, _env: Env, msg: Reply) -> ContractResponse {
+    match msg.id {
+        REPLY_ID_HOOK => Ok(Response::new()
+            .add_attribute("action", "hook_failed")
+            .add_attribute("error", msg.result.unwrap_err())),
+        _ => Err(StdError::generic_err(format!("Unknown reply id: {}", msg.id)).into()),
     }
 }
 
+/// `max_items_per_side` of a migrated legacy config, can be changed with `UpdateConfig`
+const LEGACY_MAX_ITEMS_PER_SIDE: u32 = 10;
+
 #[entry_point]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> ContractResponse {
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> ContractResponse {
+    if CONFIG.load(deps.storage).is_err() {
+        let legacy = LEGACY_CONFIG.load(deps.storage)?;
+
+        // New features start disabled, the fee goes to the single fee collector
+        let config = Config {
+            owner: legacy.owner,
+            counter_otc: legacy.counter_otc,
+            counter_bid: 0,
+            counter_ring: 0,
+            fee: legacy.fee,
+            fee_collectors: vec![FeeCollector {
+                address: legacy.fee_collector,
+                weight: Decimal::one(),
+            }],
+            auction_fee: Decimal::zero(),
+            price_oracle: None,
+            hook_policy: HookPolicy::Revert,
+            referral_share: Decimal::zero(),
+            excess_funds_policy: ExcessFundsPolicy::Refund,
+            max_items_per_side: LEGACY_MAX_ITEMS_PER_SIDE,
+            listing_bond: None,
+        };
+
+        config.validate(deps.as_ref())?;
+
+        CONFIG.save(deps.storage, &config)?;
+
+        rebuild_escrow(deps.storage)?;
+        index_legacy_positions(deps.storage)?;
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
     Ok(Response::default())
 }
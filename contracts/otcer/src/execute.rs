@@ -1,14 +1,35 @@
-use cosmwasm_std::{DepsMut, Env, MessageInfo, Response, StdError};
+use cosmwasm_std::{CosmosMsg, DepsMut, Empty, Env, MessageInfo, Response, StdError};
 use otcer_pkg::otcer::{
-    definitions::OtcPosition,
-    msgs::{CancelOtcMsg, ClaimOtcMsg, CreateOtcMsg, ExecuteOtcMsg},
+    definitions::{
+        Dispute, ExecutionCommitment, FeeCollector, ItemPricing, OtcBid, OtcItem, OtcItemInfo,
+        OtcPosition, OtcPositionStatus, RingTrade, RingTradeStatus,
+    },
+    msgs::{
+        AcceptBidMsg, AcceptUnwindMsg, AddHookMsg, ApproveMilestoneMsg, BidAuctionMsg,
+        CancelNoncesMsg, CancelOtcMsg, ClaimOtcMsg, CommitExecutionMsg, CreateOtcMsg,
+        CreateRingTradeMsg, DepositRingLegMsg, ExecuteManyMsg, ExecuteOtcMsg,
+        ExecuteSignedOrderMsg, OtcHookMsg, PlaceBidMsg, ProposeUnwindMsg, RaiseDisputeMsg,
+        RecoverStuckFundsMsg, RefundRingTradeMsg, RemoveHookMsg, ResolveDisputeMsg,
+        SettleAuctionMsg, SlashBondMsg, UpdateConfigMsg, WithdrawBidMsg,
+    },
 };
 use rhaki_cw_plus::traits::IntoAddr;
 
 use crate::{
-    functions::{after_action, cancel_otc, collect_otc_items, send_fee, send_otc_items},
+    functions::{
+        after_action, assert_not_disputed, assert_received_funds, build_escrowed_fee_msgs,
+        build_fee_collectors_msgs, build_hook_msgs, build_send_otc_info_items, cancel_otc,
+        cancelled_hook_msg, claim_otc_items, collect_listing_bond, collect_otc_info_items,
+        collect_otc_items, decrease_escrow, escrowed_amount, execute_otc,
+        execute_otc_required_items, executed_hook_msg, handle_excess_funds,
+        handle_unexpected_funds, normalize_and_validate_position,
+        normalize_and_validate_ring_trade, normalize_otc_items, prepare_execute_otc,
+        record_creation, record_execution, record_fee, record_trade, refund_bids, release_bond,
+        resolve_otc_items, send_fee, send_otc_items, unwind_otc_items, validate_otc_items,
+        validate_referrer, verify_signed_order,
+    },
     response::{ContractError, ContractResponse},
-    state::{positions, CONFIG},
+    state::{positions, BIDS, COMMITMENTS, CONFIG, HOOKS, NONCES, RING_TRADES, UNWIND_PROPOSALS},
 };
 
 pub fn run_create_otc(
@@ -20,27 +41,71 @@ pub fn run_create_otc(
     let mut config = CONFIG.load(deps.storage)?;
     config.counter_otc += 1;
 
-    let position = OtcPosition::from_create_otc_msg(
+    let referrer = validate_referrer(deps.as_ref(), msg.referrer.clone(), &info.sender)?;
+
+    let mut position = OtcPosition::from_create_otc_msg(
         deps.as_ref(),
         &env,
         msg,
         config.counter_otc,
         info.sender.clone(),
     )?;
-    position.validate(deps.as_ref())?;
+    position.validate(deps.as_ref(), &env)?;
+    normalize_and_validate_position(&config, &mut position)?;
+    position.load_metadata(deps.as_ref())?;
+
+    if config.price_oracle.is_none()
+        && position
+            .ask
+            .iter()
+            .any(|item| matches!(item.pricing, Some(ItemPricing::Oracle(..))))
+    {
+        return Err(StdError::generic_err("Price oracle not configured").into());
+    }
+
+    let (msgs_deposit, remaining_coins) = collect_otc_items(
+        deps.storage,
+        &env,
+        &position.offer,
+        info.sender.clone(),
+        info.funds,
+    )?;
+
+    let (msgs_fee, remaining_coins) = send_fee(
+        deps.storage,
+        &env,
+        &config,
+        &info.sender,
+        remaining_coins,
+        referrer.as_ref(),
+    )?;
 
-    let (msgs_deposit, remaining_coins) =
-        collect_otc_items(&env, &position.offer, info.sender, info.funds)?;
+    let (msgs_bond, remaining_coins) =
+        collect_listing_bond(deps.storage, &env, &config, &mut position, remaining_coins)?;
 
-    let msgs_fee = send_fee(&env, &config.fee, &config.fee_collector, remaining_coins)?;
+    let msgs_excess = handle_excess_funds(&config, &info.sender, remaining_coins)?;
 
     CONFIG.save(deps.storage, &config)?;
 
     positions().save(deps.storage, config.counter_otc, &position)?;
 
+    record_creation(deps.storage, &env)?;
+
+    let msgs_hook = build_hook_msgs(
+        deps.storage,
+        &position,
+        OtcHookMsg::Created {
+            id: position.id,
+            owner: position.owner.clone(),
+        },
+    )?;
+
     Ok(Response::new()
         .add_messages(msgs_deposit)
         .add_messages(msgs_fee)
+        .add_messages(msgs_bond)
+        .add_messages(msgs_excess)
+        .add_submessages(msgs_hook)
         .add_attribute("action", "create_orc")
         .add_attribute(
             "executor",
@@ -52,53 +117,506 @@ pub fn run_create_otc(
 }
 
 pub fn run_execute_otc(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     msg: ExecuteOtcMsg,
 ) -> ContractResponse {
-    let mut position = positions().load(deps.storage, msg.id)?;
-    position.active(&env, &info.sender)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    let mut position = prepare_execute_otc(
+        deps.branch(),
+        &env,
+        &config,
+        &info.sender,
+        msg.id,
+        msg.secret,
+    )?;
+
+    let referrer = validate_referrer(deps.as_ref(), msg.referrer, &info.sender)?;
+
+    let remaining_coins =
+        assert_received_funds(&execute_otc_required_items(&config, &position), info.funds)?;
+
+    let msgs_excess = handle_excess_funds(&config, &info.sender, remaining_coins)?;
+
+    let (msgs, msgs_hook, attrs_close) = execute_otc(
+        deps,
+        &env,
+        &config,
+        &info.sender,
+        &mut position,
+        referrer.as_ref(),
+    )?;
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_messages(msgs_excess)
+        .add_submessages(msgs_hook)
+        .add_attribute("action", "execute_otc")
+        .add_attribute("otc_id", msg.id.to_string())
+        .add_attributes(attrs_close))
+}
+
+pub fn run_execute_many(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteManyMsg,
+) -> ContractResponse {
+    if msg.ids.is_empty() {
+        return Err(StdError::generic_err("No position to execute").into());
+    }
 
     let config = CONFIG.load(deps.storage)?;
 
-    let (msgs_deposit, remaining_coins) =
-        collect_otc_items(&env, &position.ask, info.sender, info.funds)?;
+    let referrer = validate_referrer(deps.as_ref(), msg.referrer, &info.sender)?;
+
+    let mut positions_to_execute: Vec<OtcPosition> = vec![];
+    let mut required_items: Vec<OtcItemInfo> = vec![];
+
+    for id in &msg.ids {
+        if positions_to_execute.iter().any(|val| val.id == *id) {
+            return Err(
+                StdError::generic_err(format!("Position listed more than once: {id}")).into(),
+            );
+        }
+
+        let position = prepare_execute_otc(deps.branch(), &env, &config, &info.sender, *id, None)?;
+
+        required_items.extend(execute_otc_required_items(&config, &position));
+        positions_to_execute.push(position);
+    }
+
+    let remaining_coins = assert_received_funds(&required_items, info.funds)?;
+
+    let msgs_excess = handle_excess_funds(&config, &info.sender, remaining_coins)?;
+
+    let mut response = Response::new()
+        .add_attribute("action", "execute_many")
+        .add_attribute(
+            "otc_ids",
+            msg.ids
+                .iter()
+                .map(|val| val.to_string())
+                .collect::<Vec<String>>()
+                .join(","),
+        );
+
+    for mut position in positions_to_execute {
+        let (msgs, msgs_hook, attrs_close) = execute_otc(
+            deps.branch(),
+            &env,
+            &config,
+            &info.sender,
+            &mut position,
+            referrer.as_ref(),
+        )?;
+
+        response = response
+            .add_messages(msgs)
+            .add_submessages(msgs_hook)
+            .add_attributes(attrs_close);
+    }
+
+    Ok(response.add_messages(msgs_excess))
+}
+
+pub fn run_commit_execution(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: CommitExecutionMsg,
+) -> ContractResponse {
+    let msgs_excess = handle_unexpected_funds(deps.storage, &info)?;
+
+    let position = positions().load(deps.storage, msg.id)?;
+
+    if position.hash_lock.is_none() {
+        return Err(StdError::generic_err("Position is not hash locked").into());
+    }
+
+    if !position.status.is_in_pending() {
+        return Err(StdError::generic_err("Position is not in pending status").into());
+    }
+
+    if info.sender == position.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(executor) = &position.executor {
+        if executor != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+    }
+
+    if msg.commitment.len() != 32 {
+        return Err(StdError::generic_err("Commitment must be a SHA-256 hash").into());
+    }
+
+    COMMITMENTS.save(
+        deps.storage,
+        (msg.id, &info.sender),
+        &ExecutionCommitment {
+            commitment: msg.commitment,
+            height: env.block.height,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_messages(msgs_excess)
+        .add_attribute("action", "commit_execution")
+        .add_attribute("otc_id", msg.id.to_string()))
+}
+
+pub fn run_execute_signed_order(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteSignedOrderMsg,
+) -> ContractResponse {
+    let order = msg.order;
+
+    if order.expiry <= env.block.time.seconds() {
+        return Err(ContractError::OrderExpired {});
+    }
+
+    verify_signed_order(deps.as_ref(), &env, &order, &msg.signature, &msg.pubkey)?;
+
+    let maker = order.maker.clone().into_addr(deps.api)?;
+
+    if NONCES.has(deps.storage, (&maker, order.nonce)) {
+        return Err(ContractError::NonceUsed { nonce: order.nonce });
+    }
+
+    NONCES.save(deps.storage, (&maker, order.nonce), &Empty {})?;
+
+    let mut config = CONFIG.load(deps.storage)?;
+    config.counter_otc += 1;
+
+    let mut position = OtcPosition::from_create_otc_msg(
+        deps.as_ref(),
+        &env,
+        CreateOtcMsg {
+            executor: order.executor,
+            offer: order.offer,
+            ask: order.ask,
+            auction: None,
+            hook: None,
+            referrer: None,
+            hash_lock: None,
+            arbiter: None,
+            milestones: None,
+        },
+        config.counter_otc,
+        maker.clone(),
+    )?;
+    position.validate(deps.as_ref(), &env)?;
+    normalize_and_validate_position(&config, &mut position)?;
+    position.load_metadata(deps.as_ref())?;
+
+    if position
+        .offer
+        .iter()
+        .any(|item| matches!(item.item_info, OtcItemInfo::Token { .. }))
+    {
+        return Err(
+            StdError::generic_err("Native tokens can't be offered in a signed order").into(),
+        );
+    }
+
+    position.active(&env, &info.sender)?;
+    position.resolve_ask_pricing(deps.as_ref(), &env, config.price_oracle.as_ref())?;
+
+    record_creation(deps.storage, &env)?;
+    record_execution(deps.storage, &env, &position)?;
+
+    let (msgs_maker_deposit, _) =
+        collect_otc_items(deps.storage, &env, &position.offer, maker.clone(), vec![])?;
+
+    let (msgs_deposit, remaining_coins) = collect_otc_items(
+        deps.storage,
+        &env,
+        &position.ask,
+        info.sender.clone(),
+        info.funds,
+    )?;
+
+    let (msgs_fee, remaining_coins) = send_fee(
+        deps.storage,
+        &env,
+        &config,
+        &info.sender,
+        remaining_coins,
+        None,
+    )?;
 
-    let msgs_fee = send_fee(&env, &config.fee, &config.fee_collector, remaining_coins)?;
+    let msgs_excess = handle_excess_funds(&config, &info.sender, remaining_coins)?;
 
-    let msgs_to_owner = send_otc_items(&env, &mut position.ask, &position.status, &position.owner)?;
+    let msgs_to_owner = send_otc_items(
+        deps.storage,
+        &env,
+        &mut position.ask,
+        &position.status,
+        position.milestones.as_ref(),
+        &position.owner,
+    )?;
     let msgs_to_executor = send_otc_items(
+        deps.storage,
         &env,
         &mut position.offer,
         &position.status,
-        &position.executor.clone().unwrap(),
+        position.milestones.as_ref(),
+        &info.sender,
     )?;
 
+    CONFIG.save(deps.storage, &config)?;
+
+    let mut msgs_hook = build_hook_msgs(
+        deps.storage,
+        &position,
+        OtcHookMsg::Created {
+            id: position.id,
+            owner: position.owner.clone(),
+        },
+    )?;
+    msgs_hook.extend(build_hook_msgs(
+        deps.storage,
+        &position,
+        executed_hook_msg(&position),
+    )?);
+
     let attrs_close = after_action(deps, &env, &mut position)?;
 
     Ok(Response::new()
+        .add_messages(msgs_maker_deposit)
         .add_messages(msgs_deposit)
         .add_messages(msgs_fee)
+        .add_messages(msgs_excess)
         .add_messages(msgs_to_owner)
         .add_messages(msgs_to_executor)
-        .add_attribute("action", "execute_otc")
-        .add_attribute("otc_id", msg.id.to_string())
+        .add_submessages(msgs_hook)
+        .add_attribute("action", "execute_signed_order")
+        .add_attribute("otc_id", config.counter_otc.to_string())
+        .add_attribute("maker", maker)
+        .add_attribute("nonce", order.nonce.to_string())
         .add_attributes(attrs_close))
 }
 
+pub fn run_cancel_nonces(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: CancelNoncesMsg,
+) -> ContractResponse {
+    let msgs_excess = handle_unexpected_funds(deps.storage, &info)?;
+
+    let mut cancelled: Vec<String> = vec![];
+    let mut skipped: Vec<String> = vec![];
+
+    // Nonces already used or cancelled don't fail the batch
+    for nonce in msg.nonces {
+        if NONCES.has(deps.storage, (&info.sender, nonce)) {
+            skipped.push(nonce.to_string());
+        } else {
+            NONCES.save(deps.storage, (&info.sender, nonce), &Empty {})?;
+            cancelled.push(nonce.to_string());
+        }
+    }
+
+    // Empty attribute values are rejected by the chain
+    Ok(Response::new()
+        .add_messages(msgs_excess)
+        .add_attribute("action", "cancel_nonces")
+        .add_attributes(
+            [("nonces", cancelled), ("skipped_nonces", skipped)]
+                .into_iter()
+                .filter(|(_, nonces)| !nonces.is_empty())
+                .map(|(key, nonces)| (key, nonces.join(","))),
+        ))
+}
+
+pub fn run_create_ring_trade(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: CreateRingTradeMsg,
+) -> ContractResponse {
+    let mut config = CONFIG.load(deps.storage)?;
+    config.counter_ring += 1;
+
+    let mut ring_trade = RingTrade::from_create_ring_trade_msg(
+        deps.as_ref(),
+        &env,
+        msg,
+        config.counter_ring,
+        info.sender.clone(),
+    )?;
+    ring_trade.validate(deps.as_ref(), &env)?;
+    normalize_and_validate_ring_trade(&config, &mut ring_trade)?;
+    ring_trade.load_metadata(deps.as_ref())?;
+
+    let (msgs_fee, remaining_coins) =
+        send_fee(deps.storage, &env, &config, &info.sender, info.funds, None)?;
+
+    let msgs_excess = handle_excess_funds(&config, &info.sender, remaining_coins)?;
+
+    CONFIG.save(deps.storage, &config)?;
+
+    RING_TRADES.save(deps.storage, ring_trade.id, &ring_trade)?;
+
+    Ok(Response::new()
+        .add_messages(msgs_fee)
+        .add_messages(msgs_excess)
+        .add_attribute("action", "create_ring_trade")
+        .add_attribute("ring_id", ring_trade.id.to_string()))
+}
+
+pub fn run_deposit_ring_leg(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: DepositRingLegMsg,
+) -> ContractResponse {
+    let config = CONFIG.load(deps.storage)?;
+
+    let mut ring_trade = RING_TRADES.load(deps.storage, msg.id)?;
+
+    if ring_trade.status != RingTradeStatus::Pending {
+        return Err(StdError::generic_err("Ring trade is not in pending status").into());
+    }
+
+    if env.block.time.seconds() >= ring_trade.deadline {
+        return Err(StdError::generic_err("Ring trade deadline passed").into());
+    }
+
+    let leg = ring_trade
+        .legs
+        .iter_mut()
+        .find(|val| val.participant == info.sender)
+        .ok_or(ContractError::Unauthorized {})?;
+
+    if leg.deposited {
+        return Err(StdError::generic_err("Leg already deposited").into());
+    }
+
+    leg.deposited = true;
+
+    let (mut msgs, remaining_coins) = collect_otc_items(
+        deps.storage,
+        &env,
+        &leg.items,
+        info.sender.clone(),
+        info.funds,
+    )?;
+
+    let msgs_excess = handle_excess_funds(&config, &info.sender, remaining_coins)?;
+
+    let mut response = Response::new()
+        .add_attribute("action", "deposit_ring_leg")
+        .add_attribute("ring_id", msg.id.to_string());
+
+    if ring_trade.is_fully_deposited() {
+        for leg in &ring_trade.legs {
+            msgs.extend(build_send_otc_info_items(
+                deps.storage,
+                &env,
+                &leg.items.iter().map(|val| val.item_info.clone()).collect(),
+                &leg.recipient,
+            )?);
+        }
+
+        ring_trade.status = RingTradeStatus::Settled(env.block.time.seconds());
+
+        record_trade(
+            deps.storage,
+            &env,
+            ring_trade
+                .legs
+                .iter()
+                .flat_map(|leg| leg.items.iter().map(|val| &val.item_info))
+                .collect(),
+            ring_trade.legs.iter().map(|leg| &leg.participant).collect(),
+        )?;
+
+        response = response.add_attribute("status", "settled");
+    }
+
+    RING_TRADES.save(deps.storage, msg.id, &ring_trade)?;
+
+    Ok(response.add_messages(msgs).add_messages(msgs_excess))
+}
+
+pub fn run_refund_ring_trade(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: RefundRingTradeMsg,
+) -> ContractResponse {
+    let msgs_excess = handle_unexpected_funds(deps.storage, &info)?;
+
+    let mut ring_trade = RING_TRADES.load(deps.storage, msg.id)?;
+
+    if ring_trade.status != RingTradeStatus::Pending {
+        return Err(StdError::generic_err("Ring trade is not in pending status").into());
+    }
+
+    if env.block.time.seconds() < ring_trade.deadline {
+        return Err(StdError::generic_err("Ring trade deadline not passed").into());
+    }
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+
+    for leg in ring_trade.legs.iter().filter(|val| val.deposited) {
+        msgs.extend(build_send_otc_info_items(
+            deps.storage,
+            &env,
+            &leg.items.iter().map(|val| val.item_info.clone()).collect(),
+            &leg.participant,
+        )?);
+    }
+
+    ring_trade.status = RingTradeStatus::Refunded(env.block.time.seconds());
+
+    RING_TRADES.save(deps.storage, msg.id, &ring_trade)?;
+
+    Ok(Response::new()
+        .add_messages(msgs_excess)
+        .add_messages(msgs)
+        .add_attribute("action", "refund_ring_trade")
+        .add_attribute("ring_id", msg.id.to_string()))
+}
+
 pub fn run_claim_otc(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     msg: ClaimOtcMsg,
 ) -> ContractResponse {
+    let msgs_excess = handle_unexpected_funds(deps.storage, &info)?;
+
     let mut position = positions().load(deps.storage, msg.id)?;
 
+    assert_not_disputed(&position)?;
+
     let msgs = if info.sender == position.owner {
-        send_otc_items(&env, &mut position.ask, &position.status, &info.sender)?
+        claim_otc_items(
+            deps.storage,
+            &env,
+            &mut position.ask,
+            &position.status,
+            position.milestones.as_ref(),
+            &info.sender,
+        )?
     } else if info.sender == position.executor.clone().unwrap() {
-        send_otc_items(&env, &mut position.offer, &position.status, &info.sender)?
+        claim_otc_items(
+            deps.storage,
+            &env,
+            &mut position.offer,
+            &position.status,
+            position.milestones.as_ref(),
+            &info.sender,
+        )?
     } else {
         return Err(ContractError::Unauthorized {});
     };
@@ -107,10 +625,21 @@ pub fn run_claim_otc(
         return Err(StdError::generic_err("Nothing to claim").into());
     }
 
+    let msgs_hook = build_hook_msgs(
+        deps.storage,
+        &position,
+        OtcHookMsg::Claimed {
+            id: position.id,
+            claimer: info.sender,
+        },
+    )?;
+
     let attrs_close = after_action(deps, &env, &mut position)?;
 
     Ok(Response::new()
+        .add_messages(msgs_excess)
         .add_messages(msgs)
+        .add_submessages(msgs_hook)
         .add_attribute("action", "claim")
         .add_attribute("id", msg.id.to_string())
         .add_attributes(attrs_close))
@@ -122,7 +651,9 @@ pub fn run_cancel_otc(
     info: MessageInfo,
     msg: CancelOtcMsg,
 ) -> ContractResponse {
-    let position = positions().load(deps.storage, msg.id)?;
+    let msgs_excess = handle_unexpected_funds(deps.storage, &info)?;
+
+    let mut position = positions().load(deps.storage, msg.id)?;
 
     if info.sender != position.owner {
         return Err(ContractError::Unauthorized {});
@@ -131,12 +662,879 @@ pub fn run_cancel_otc(
     if !position.status.is_in_pending() {
         return Err(StdError::generic_err("Can't cancel a position non in pending status").into());
     }
-    let msgs_to_owner = cancel_otc(&env, &position)?;
+
+    assert_not_disputed(&position)?;
+
+    if let Some(auction) = &position.auction {
+        if auction.highest_bid.is_some() {
+            return Err(StdError::generic_err("Can't cancel an auction with bids").into());
+        }
+    }
+    let msgs_to_owner = cancel_otc(deps.storage, &env, &position)?;
+    let msgs_refund_bids = refund_bids(deps.storage, &env, msg.id)?;
+
+    // a position cancelled after the expiry of the bond is slashed as if untouched
+    let slash = position.is_expired(&env);
+    let msgs_bond = release_bond(deps.storage, &env, &mut position, slash)?;
 
     positions().remove(deps.storage, msg.id)?;
 
+    let msgs_hook = build_hook_msgs(deps.storage, &position, cancelled_hook_msg(&position))?;
+
     Ok(Response::new()
+        .add_messages(msgs_excess)
         .add_messages(msgs_to_owner)
+        .add_messages(msgs_refund_bids)
+        .add_messages(msgs_bond)
+        .add_submessages(msgs_hook)
         .add_attribute("action", "cancel_otc")
         .add_attribute("id", msg.id.to_string()))
 }
+
+pub fn run_slash_bond(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: SlashBondMsg,
+) -> ContractResponse {
+    let msgs_excess = handle_unexpected_funds(deps.storage, &info)?;
+
+    let mut position = positions().load(deps.storage, msg.id)?;
+
+    if !position.status.is_in_pending() {
+        return Err(StdError::generic_err("Can't slash a position non in pending status").into());
+    }
+
+    assert_not_disputed(&position)?;
+
+    if !position.is_expired(&env) {
+        return Err(StdError::generic_err("Listing bond not expired").into());
+    }
+
+    let msgs_to_owner = cancel_otc(deps.storage, &env, &position)?;
+    let msgs_refund_bids = refund_bids(deps.storage, &env, msg.id)?;
+    let msgs_bond = release_bond(deps.storage, &env, &mut position, true)?;
+
+    positions().remove(deps.storage, msg.id)?;
+
+    let msgs_hook = build_hook_msgs(deps.storage, &position, cancelled_hook_msg(&position))?;
+
+    Ok(Response::new()
+        .add_messages(msgs_excess)
+        .add_messages(msgs_to_owner)
+        .add_messages(msgs_refund_bids)
+        .add_messages(msgs_bond)
+        .add_submessages(msgs_hook)
+        .add_attribute("action", "slash_bond")
+        .add_attribute("id", msg.id.to_string()))
+}
+
+pub fn run_propose_unwind(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ProposeUnwindMsg,
+) -> ContractResponse {
+    let msgs_excess = handle_unexpected_funds(deps.storage, &info)?;
+
+    let position = positions().load(deps.storage, msg.id)?;
+
+    if info.sender != position.owner && Some(&info.sender) != position.executor.as_ref() {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if !matches!(position.status, OtcPositionStatus::Vesting(..)) {
+        return Err(StdError::generic_err("Can't unwind a position non in vesting status").into());
+    }
+
+    assert_not_disputed(&position)?;
+
+    UNWIND_PROPOSALS.save(deps.storage, msg.id, &info.sender)?;
+
+    Ok(Response::new()
+        .add_messages(msgs_excess)
+        .add_attribute("action", "propose_unwind")
+        .add_attribute("id", msg.id.to_string())
+        .add_attribute("proposer", info.sender))
+}
+
+pub fn run_accept_unwind(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: AcceptUnwindMsg,
+) -> ContractResponse {
+    let msgs_excess = handle_unexpected_funds(deps.storage, &info)?;
+
+    let mut position = positions().load(deps.storage, msg.id)?;
+
+    if !matches!(position.status, OtcPositionStatus::Vesting(..)) {
+        return Err(StdError::generic_err("Can't unwind a position non in vesting status").into());
+    }
+
+    assert_not_disputed(&position)?;
+
+    let executor = position.executor.clone().unwrap();
+
+    let proposer = UNWIND_PROPOSALS
+        .may_load(deps.storage, msg.id)?
+        .ok_or(StdError::generic_err("Unwind not proposed"))?;
+
+    let counterparty = if proposer == position.owner {
+        &executor
+    } else {
+        &position.owner
+    };
+
+    if info.sender != *counterparty {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut msgs = unwind_otc_items(deps.storage, &env, &mut position.offer, &position.owner)?;
+    msgs.extend(unwind_otc_items(
+        deps.storage,
+        &env,
+        &mut position.ask,
+        &executor,
+    )?);
+
+    position.status = OtcPositionStatus::Unwound(env.block.time.seconds());
+
+    positions().save(deps.storage, msg.id, &position)?;
+
+    UNWIND_PROPOSALS.remove(deps.storage, msg.id);
+
+    let msgs_hook = build_hook_msgs(
+        deps.storage,
+        &position,
+        OtcHookMsg::Unwound {
+            id: position.id,
+            owner: position.owner.clone(),
+            executor,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_messages(msgs_excess)
+        .add_messages(msgs)
+        .add_submessages(msgs_hook)
+        .add_attribute("action", "accept_unwind")
+        .add_attribute("id", msg.id.to_string()))
+}
+
+pub fn run_approve_milestone(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ApproveMilestoneMsg,
+) -> ContractResponse {
+    let msgs_excess = handle_unexpected_funds(deps.storage, &info)?;
+
+    let mut position = positions().load(deps.storage, msg.id)?;
+
+    if !matches!(
+        position.status,
+        OtcPositionStatus::Pending | OtcPositionStatus::Vesting(..)
+    ) {
+        return Err(StdError::generic_err(
+            "Can't approve milestones of a position non in pending or vesting status",
+        )
+        .into());
+    }
+
+    assert_not_disputed(&position)?;
+
+    let plan = position
+        .milestones
+        .as_mut()
+        .ok_or(StdError::generic_err("Position has no milestones"))?;
+
+    let milestone =
+        plan.milestones
+            .get_mut(msg.milestone as usize)
+            .ok_or(StdError::generic_err(format!(
+                "Milestone {} not defined",
+                msg.milestone
+            )))?;
+
+    if milestone.completed.is_some() {
+        return Err(StdError::generic_err("Milestone already completed").into());
+    }
+
+    match &plan.approver {
+        Some(approver) => {
+            if info.sender != *approver {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            milestone.completed = Some(env.block.time.seconds());
+        }
+        None => {
+            let executor = position.executor.as_ref();
+
+            if info.sender != position.owner && Some(&info.sender) != executor {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            if milestone.approvals.contains(&info.sender) {
+                return Err(StdError::generic_err("Milestone already approved").into());
+            }
+
+            milestone.approvals.push(info.sender.clone());
+
+            if milestone.approvals.contains(&position.owner)
+                && executor.is_some_and(|executor| milestone.approvals.contains(executor))
+            {
+                milestone.completed = Some(env.block.time.seconds());
+            }
+        }
+    }
+
+    let completed = milestone.completed.is_some();
+
+    positions().save(deps.storage, msg.id, &position)?;
+
+    Ok(Response::new()
+        .add_messages(msgs_excess)
+        .add_attribute("action", "approve_milestone")
+        .add_attribute("id", msg.id.to_string())
+        .add_attribute("milestone", msg.milestone.to_string())
+        .add_attribute("completed", completed.to_string()))
+}
+
+pub fn run_raise_dispute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: RaiseDisputeMsg,
+) -> ContractResponse {
+    let msgs_excess = handle_unexpected_funds(deps.storage, &info)?;
+
+    let mut position = positions().load(deps.storage, msg.id)?;
+
+    if info.sender != position.owner && Some(&info.sender) != position.executor.as_ref() {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if position.arbiter.is_none() {
+        return Err(StdError::generic_err("Position has no arbiter").into());
+    }
+
+    if !matches!(
+        position.status,
+        OtcPositionStatus::Pending | OtcPositionStatus::Vesting(..)
+    ) {
+        return Err(StdError::generic_err(
+            "Can't dispute a position non in pending or vesting status",
+        )
+        .into());
+    }
+
+    assert_not_disputed(&position)?;
+
+    position.dispute = Some(Dispute {
+        raised_by: info.sender.clone(),
+        time: env.block.time.seconds(),
+    });
+
+    positions().save(deps.storage, msg.id, &position)?;
+
+    Ok(Response::new()
+        .add_messages(msgs_excess)
+        .add_attribute("action", "raise_dispute")
+        .add_attribute("id", msg.id.to_string())
+        .add_attribute("raised_by", info.sender))
+}
+
+pub fn run_resolve_dispute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ResolveDisputeMsg,
+) -> ContractResponse {
+    let msgs_excess = handle_unexpected_funds(deps.storage, &info)?;
+
+    let mut position = positions().load(deps.storage, msg.id)?;
+
+    let arbiter = position
+        .arbiter
+        .clone()
+        .ok_or(StdError::generic_err("Position has no arbiter"))?;
+
+    if info.sender != arbiter.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let dispute = position
+        .dispute
+        .take()
+        .ok_or(StdError::generic_err("Position is not under dispute"))?;
+
+    let settle_share = msg.resolution.settle_share()?;
+
+    let msgs = if position.status.is_in_pending() {
+        // only the offer is escrowed and the executor has nothing to receive
+        if !settle_share.is_zero() {
+            return Err(StdError::generic_err("A pending position can only be refunded").into());
+        }
+
+        let mut msgs = resolve_otc_items(
+            deps.storage,
+            &env,
+            &mut position.offer,
+            &position.status,
+            &arbiter,
+            settle_share,
+            (&position.owner, &position.owner),
+        )?;
+        msgs.extend(refund_bids(deps.storage, &env, msg.id)?);
+        msgs.extend(release_bond(deps.storage, &env, &mut position, false)?);
+        msgs
+    } else {
+        let executor = position.executor.clone().unwrap();
+
+        let mut msgs = resolve_otc_items(
+            deps.storage,
+            &env,
+            &mut position.offer,
+            &position.status,
+            &arbiter,
+            settle_share,
+            (&position.owner, &executor),
+        )?;
+        msgs.extend(resolve_otc_items(
+            deps.storage,
+            &env,
+            &mut position.ask,
+            &position.status,
+            &arbiter,
+            settle_share,
+            (&executor, &position.owner),
+        )?);
+        msgs
+    };
+
+    position.status = OtcPositionStatus::Arbitrated(env.block.time.seconds());
+
+    positions().save(deps.storage, msg.id, &position)?;
+
+    UNWIND_PROPOSALS.remove(deps.storage, msg.id);
+
+    Ok(Response::new()
+        .add_messages(msgs_excess)
+        .add_messages(msgs)
+        .add_attribute("action", "resolve_dispute")
+        .add_attribute("id", msg.id.to_string())
+        .add_attribute("raised_by", dispute.raised_by)
+        .add_attribute("resolution", msg.resolution.as_string()))
+}
+
+pub fn run_bid_auction(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: BidAuctionMsg,
+) -> ContractResponse {
+    let mut position = positions().load(deps.storage, msg.id)?;
+
+    if !position.status.is_in_pending() {
+        return Err(StdError::generic_err("Can't bid on a position non in pending status").into());
+    }
+
+    if info.sender == position.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let auction = position
+        .auction
+        .as_mut()
+        .ok_or(StdError::generic_err("Position is not an auction"))?;
+
+    let bid_item = OtcItem {
+        item_info: auction.reserve.with_amount(msg.amount)?,
+        vesting_info: None,
+        pricing: None,
+        metadata: None,
+        current_amount: None,
+    };
+
+    let outbid = auction.place_bid(&env, info.sender.clone(), msg.amount)?;
+    let end_time = auction.end_time;
+
+    let (msgs_deposit, remaining_coins) = collect_otc_items(
+        deps.storage,
+        &env,
+        &vec![bid_item],
+        info.sender.clone(),
+        info.funds,
+    )?;
+
+    let config = CONFIG.load(deps.storage)?;
+
+    let msgs_excess = handle_excess_funds(&config, &info.sender, remaining_coins)?;
+
+    let mut msgs_refund = vec![];
+
+    if let Some(outbid) = &outbid {
+        decrease_escrow(deps.storage, &auction.reserve, outbid.amount)?;
+
+        msgs_refund.push(auction.reserve.with_amount(outbid.amount)?.build_send_msg(
+            &env,
+            &env.contract.address,
+            &outbid.bidder,
+            None,
+        )?);
+    }
+
+    positions().save(deps.storage, msg.id, &position)?;
+
+    Ok(Response::new()
+        .add_messages(msgs_deposit)
+        .add_messages(msgs_excess)
+        .add_messages(msgs_refund)
+        .add_attribute("action", "bid_auction")
+        .add_attribute("otc_id", msg.id.to_string())
+        .add_attribute("amount", msg.amount)
+        .add_attribute("end_time", end_time.to_string()))
+}
+
+pub fn run_settle_auction(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: SettleAuctionMsg,
+) -> ContractResponse {
+    let msgs_excess = handle_unexpected_funds(deps.storage, &info)?;
+
+    let mut position = positions().load(deps.storage, msg.id)?;
+
+    if !position.status.is_in_pending() {
+        return Err(StdError::generic_err("Auction already settled").into());
+    }
+
+    let auction = position
+        .auction
+        .clone()
+        .ok_or(StdError::generic_err("Position is not an auction"))?;
+
+    if !auction.is_ended(&env) {
+        return Err(StdError::generic_err("Auction is not ended").into());
+    }
+
+    let Some(winning_bid) = auction.highest_bid else {
+        let msgs_to_owner = cancel_otc(deps.storage, &env, &position)?;
+
+        positions().remove(deps.storage, msg.id)?;
+
+        let msgs_hook = build_hook_msgs(deps.storage, &position, cancelled_hook_msg(&position))?;
+
+        return Ok(Response::new()
+            .add_messages(msgs_excess)
+            .add_messages(msgs_to_owner)
+            .add_submessages(msgs_hook)
+            .add_attribute("action", "settle_auction")
+            .add_attribute("otc_id", msg.id.to_string())
+            .add_attribute("winner", "undefined"));
+    };
+
+    let config = CONFIG.load(deps.storage)?;
+
+    let fee_amount = winning_bid.amount * config.auction_fee;
+
+    let msgs_fee = if !fee_amount.is_zero() {
+        decrease_escrow(deps.storage, &auction.reserve, fee_amount)?;
+        record_fee(deps.storage, &env, &auction.reserve, fee_amount)?;
+
+        build_fee_collectors_msgs(
+            &env,
+            &config,
+            &auction.reserve,
+            &env.contract.address,
+            fee_amount,
+        )?
+    } else {
+        vec![]
+    };
+
+    position.ask = vec![OtcItem {
+        item_info: auction
+            .reserve
+            .with_amount(winning_bid.amount - fee_amount)?,
+        vesting_info: None,
+        pricing: None,
+        metadata: auction.reserve.query_metadata(deps.as_ref())?,
+        current_amount: None,
+    }];
+
+    position.active(&env, &winning_bid.bidder)?;
+
+    record_execution(deps.storage, &env, &position)?;
+
+    let msgs_to_owner = send_otc_items(
+        deps.storage,
+        &env,
+        &mut position.ask,
+        &position.status,
+        position.milestones.as_ref(),
+        &position.owner,
+    )?;
+    let msgs_to_executor = send_otc_items(
+        deps.storage,
+        &env,
+        &mut position.offer,
+        &position.status,
+        position.milestones.as_ref(),
+        &winning_bid.bidder,
+    )?;
+
+    let msgs_hook = build_hook_msgs(deps.storage, &position, executed_hook_msg(&position))?;
+
+    let attrs_close = after_action(deps, &env, &mut position)?;
+
+    Ok(Response::new()
+        .add_messages(msgs_excess)
+        .add_messages(msgs_fee)
+        .add_messages(msgs_to_owner)
+        .add_messages(msgs_to_executor)
+        .add_submessages(msgs_hook)
+        .add_attribute("action", "settle_auction")
+        .add_attribute("otc_id", msg.id.to_string())
+        .add_attribute("winner", winning_bid.bidder)
+        .add_attribute("amount", winning_bid.amount)
+        .add_attributes(attrs_close))
+}
+
+pub fn run_place_bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: PlaceBidMsg,
+) -> ContractResponse {
+    let position = positions().load(deps.storage, msg.id)?;
+
+    if !position.status.is_in_pending() {
+        return Err(StdError::generic_err("Can't bid on a position non in pending status").into());
+    }
+
+    assert_not_disputed(&position)?;
+
+    if position.auction.is_some() {
+        return Err(StdError::generic_err("Use BidAuction for auction positions").into());
+    }
+
+    if position.hash_lock.is_some() {
+        return Err(StdError::generic_err("Bids are not allowed on hash locked positions").into());
+    }
+
+    if info.sender == position.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(executor) = &position.executor {
+        if executor != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+    }
+
+    let mut config = CONFIG.load(deps.storage)?;
+    config.counter_bid += 1;
+
+    let mut bid = OtcBid {
+        id: config.counter_bid,
+        position_id: msg.id,
+        bidder: info.sender.clone(),
+        items: normalize_otc_items(msg.items.into_iter().map(|val| val.into()).collect())?,
+        creation_time: env.block.time.seconds(),
+        fee: config.fee.clone(),
+    };
+    bid.validate(deps.as_ref())?;
+    validate_otc_items(&config, &bid.items, "bid")?;
+
+    for item in bid.items.iter_mut() {
+        item.metadata = item.item_info.query_metadata(deps.as_ref())?;
+    }
+
+    let (msgs_deposit, remaining_coins) = collect_otc_items(
+        deps.storage,
+        &env,
+        &bid.items,
+        info.sender.clone(),
+        info.funds,
+    )?;
+
+    let (msgs_fee, remaining_coins) =
+        collect_otc_info_items(deps.storage, &env, &bid.fee, &info.sender, remaining_coins)?;
+
+    let msgs_excess = handle_excess_funds(&config, &info.sender, remaining_coins)?;
+
+    CONFIG.save(deps.storage, &config)?;
+
+    BIDS.save(deps.storage, (msg.id, bid.id), &bid)?;
+
+    Ok(Response::new()
+        .add_messages(msgs_deposit)
+        .add_messages(msgs_fee)
+        .add_messages(msgs_excess)
+        .add_attribute("action", "place_bid")
+        .add_attribute("otc_id", msg.id.to_string())
+        .add_attribute("bid_id", bid.id.to_string()))
+}
+
+pub fn run_withdraw_bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: WithdrawBidMsg,
+) -> ContractResponse {
+    let msgs_excess = handle_unexpected_funds(deps.storage, &info)?;
+
+    let bid = BIDS.load(deps.storage, (msg.id, msg.bid_id))?;
+
+    if info.sender != bid.bidder {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let msgs_refund =
+        build_send_otc_info_items(deps.storage, &env, &bid.escrowed_items(), &bid.bidder)?;
+
+    BIDS.remove(deps.storage, (msg.id, msg.bid_id));
+
+    Ok(Response::new()
+        .add_messages(msgs_excess)
+        .add_messages(msgs_refund)
+        .add_attribute("action", "withdraw_bid")
+        .add_attribute("otc_id", msg.id.to_string())
+        .add_attribute("bid_id", msg.bid_id.to_string()))
+}
+
+pub fn run_accept_bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: AcceptBidMsg,
+) -> ContractResponse {
+    let msgs_excess = handle_unexpected_funds(deps.storage, &info)?;
+
+    let mut position = positions().load(deps.storage, msg.id)?;
+
+    if info.sender != position.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    assert_not_disputed(&position)?;
+
+    if position.is_expired(&env) {
+        return Err(StdError::generic_err("Position expired").into());
+    }
+
+    let bid = BIDS.load(deps.storage, (msg.id, msg.bid_id))?;
+    BIDS.remove(deps.storage, (msg.id, msg.bid_id));
+
+    let config = CONFIG.load(deps.storage)?;
+    let msgs_fee = build_escrowed_fee_msgs(deps.storage, &env, &config, &bid.fee)?;
+
+    position.ask = bid.items;
+    position.active(&env, &bid.bidder)?;
+
+    record_execution(deps.storage, &env, &position)?;
+
+    let msgs_to_owner = send_otc_items(
+        deps.storage,
+        &env,
+        &mut position.ask,
+        &position.status,
+        position.milestones.as_ref(),
+        &position.owner,
+    )?;
+    let msgs_to_executor = send_otc_items(
+        deps.storage,
+        &env,
+        &mut position.offer,
+        &position.status,
+        position.milestones.as_ref(),
+        &bid.bidder,
+    )?;
+
+    let msgs_refund_bids = refund_bids(deps.storage, &env, msg.id)?;
+
+    let msgs_bond = release_bond(deps.storage, &env, &mut position, false)?;
+
+    let msgs_hook = build_hook_msgs(deps.storage, &position, executed_hook_msg(&position))?;
+
+    let attrs_close = after_action(deps, &env, &mut position)?;
+
+    Ok(Response::new()
+        .add_messages(msgs_excess)
+        .add_messages(msgs_to_owner)
+        .add_messages(msgs_to_executor)
+        .add_messages(msgs_refund_bids)
+        .add_messages(msgs_bond)
+        .add_messages(msgs_fee)
+        .add_submessages(msgs_hook)
+        .add_attribute("action", "accept_bid")
+        .add_attribute("otc_id", msg.id.to_string())
+        .add_attribute("bid_id", msg.bid_id.to_string())
+        .add_attributes(attrs_close))
+}
+
+pub fn run_update_config(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: UpdateConfigMsg,
+) -> ContractResponse {
+    let msgs_excess = handle_unexpected_funds(deps.storage, &info)?;
+
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(owner) = msg.owner {
+        config.owner = owner.into_addr(deps.api)?;
+    }
+
+    if let Some(fee) = msg.fee {
+        config.fee = fee;
+    }
+
+    if let Some(fee_collectors) = msg.fee_collectors {
+        config.fee_collectors = FeeCollector::from_registrations(deps.as_ref(), fee_collectors)?;
+    }
+
+    if let Some(auction_fee) = msg.auction_fee {
+        config.auction_fee = auction_fee;
+    }
+
+    if let Some(price_oracle) = msg.price_oracle {
+        config.price_oracle = Some(price_oracle.into_addr(deps.api)?);
+    }
+
+    if let Some(hook_policy) = msg.hook_policy {
+        config.hook_policy = hook_policy;
+    }
+
+    if let Some(referral_share) = msg.referral_share {
+        config.referral_share = referral_share;
+    }
+
+    if let Some(excess_funds_policy) = msg.excess_funds_policy {
+        config.excess_funds_policy = excess_funds_policy;
+    }
+
+    if let Some(max_items_per_side) = msg.max_items_per_side {
+        config.max_items_per_side = max_items_per_side;
+    }
+
+    if let Some(listing_bond) = msg.listing_bond {
+        config.listing_bond = if listing_bond.asset.get_amount().is_zero() {
+            None
+        } else {
+            Some(listing_bond)
+        };
+    }
+
+    config.validate(deps.as_ref())?;
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_messages(msgs_excess)
+        .add_attribute("action", "update_config"))
+}
+
+pub fn run_add_hook(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: AddHookMsg,
+) -> ContractResponse {
+    let msgs_excess = handle_unexpected_funds(deps.storage, &info)?;
+
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let contract = msg.contract.into_addr(deps.api)?;
+
+    HOOKS.save(deps.storage, &contract, &Empty {})?;
+
+    Ok(Response::new()
+        .add_messages(msgs_excess)
+        .add_attribute("action", "add_hook")
+        .add_attribute("contract", contract))
+}
+
+pub fn run_remove_hook(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: RemoveHookMsg,
+) -> ContractResponse {
+    let msgs_excess = handle_unexpected_funds(deps.storage, &info)?;
+
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let contract = msg.contract.into_addr(deps.api)?;
+
+    if !HOOKS.has(deps.storage, &contract) {
+        return Err(StdError::generic_err(format!("Hook not registered: {contract}")).into());
+    }
+
+    HOOKS.remove(deps.storage, &contract);
+
+    Ok(Response::new()
+        .add_messages(msgs_excess)
+        .add_attribute("action", "remove_hook")
+        .add_attribute("contract", contract))
+}
+
+pub fn run_recover_stuck_funds(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: RecoverStuckFundsMsg,
+) -> ContractResponse {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Funds sent with the message would be counted as stuck
+    if !info.funds.is_empty() {
+        return Err(StdError::generic_err("Funds not allowed").into());
+    }
+
+    let recipient = msg.recipient.into_addr(deps.api)?;
+
+    let recoverable = msg
+        .asset
+        .query_balance(deps.as_ref(), &env.contract.address)?
+        .saturating_sub(escrowed_amount(deps.storage, &msg.asset)?);
+
+    if msg.asset.get_amount() > recoverable {
+        return Err(StdError::generic_err(format!(
+            "Amount not recoverable: requested: {}, recoverable: {recoverable}",
+            msg.asset.get_amount()
+        ))
+        .into());
+    }
+
+    let msg_recover = msg
+        .asset
+        .build_send_msg(&env, &env.contract.address, &recipient, None)?;
+
+    Ok(Response::new()
+        .add_message(msg_recover)
+        .add_attribute("action", "recover_stuck_funds")
+        .add_attribute("asset", msg.asset.asset_key())
+        .add_attribute("recipient", recipient))
+}
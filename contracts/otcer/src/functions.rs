@@ -1,11 +1,236 @@
+use bech32::{ToBase32, Variant};
 use cosmwasm_std::{
-    attr, Addr, Attribute, Coin, CosmosMsg, DepsMut, Env, StdError, StdResult, Uint128,
+    attr, Addr, Attribute, BankMsg, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut, Empty, Env,
+    MessageInfo, Order, StdError, StdResult, Storage, SubMsg, Uint128, WasmMsg,
 };
-use otcer_pkg::otcer::definitions::{OtcItem, OtcItemInfo, OtcPosition, OtcPositionStatus};
+use otcer_pkg::otcer::{
+    definitions::{
+        Arbiter, AssetAmount, BondStatus, Config, DailyStats, ExcessFundsPolicy, FeeCollector,
+        HookPolicy, MilestonePlan, OtcBid, OtcItem, OtcItemInfo, OtcPosition, OtcPositionStatus,
+        PositionBond, RingTrade,
+    },
+    msgs::{OtcHookExecuteMsg, OtcHookMsg, SignedOrder, SignedOrderPayload},
+};
+use rhaki_cw_plus::{
+    traits::{IntoAddr, IntoBinary},
+    wasm::WasmMsgBuilder,
+};
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    response::ContractError,
+    state::{
+        positions, BIDS, COMMITMENTS, CONFIG, DAILY_STATS, ESCROW, FEES, HOOKS, REFERRAL_TOTALS,
+        TRADERS, TRADERS_COUNT, VOLUMES,
+    },
+};
+
+pub const REPLY_ID_HOOK: u64 = 1;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Merge the fungible items of the same asset with the same vesting and without pricing.
+///
+/// Any other asset listed more than once is rejected
+pub fn normalize_otc_items(items: Vec<OtcItem>) -> Result<Vec<OtcItem>, ContractError> {
+    let mut normalized: Vec<OtcItem> = vec![];
+
+    for item in items {
+        let asset_key = item.item_info.asset_key();
+
+        match normalized
+            .iter_mut()
+            .find(|val| val.item_info.asset_key() == asset_key)
+        {
+            Some(existing)
+                if !matches!(item.item_info, OtcItemInfo::Cw721 { .. })
+                    && item.pricing.is_none()
+                    && existing.pricing.is_none()
+                    && item.vesting_info == existing.vesting_info =>
+            {
+                existing.item_info = existing
+                    .item_info
+                    .with_amount(existing.item_info.get_amount() + item.item_info.get_amount())?;
+            }
+            Some(_) => return Err(ContractError::DuplicateAsset { asset: asset_key }),
+            None => normalized.push(item),
+        }
+    }
+
+    Ok(normalized)
+}
+
+/// Check the number of items of a side against `Config.max_items_per_side` and reject zero amounts
+pub fn validate_otc_items(
+    config: &Config,
+    items: &[OtcItem],
+    side: &str,
+) -> Result<(), ContractError> {
+    if items.is_empty() {
+        return Err(ContractError::EmptyItems {
+            side: side.to_string(),
+        });
+    }
+
+    if items.len() > config.max_items_per_side as usize {
+        return Err(ContractError::TooManyItems {
+            side: side.to_string(),
+            max: config.max_items_per_side,
+        });
+    }
+
+    for item in items {
+        if item.item_info.get_amount().is_zero() {
+            return Err(ContractError::ZeroAmount {
+                asset: item.item_info.asset_key(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Normalise the items of a new position and apply the checks not covered by `OtcPosition::validate`
+pub fn normalize_and_validate_position(
+    config: &Config,
+    position: &mut OtcPosition,
+) -> Result<(), ContractError> {
+    if position.executor.as_ref() == Some(&position.owner) {
+        return Err(ContractError::ExecutorIsOwner {});
+    }
+
+    position.offer = normalize_otc_items(position.offer.clone())?;
+    position.ask = normalize_otc_items(position.ask.clone())?;
+
+    validate_otc_items(config, &position.offer, "offer")?;
+
+    // the ask of an auction is defined by the winning bid
+    if position.auction.is_none() {
+        validate_otc_items(config, &position.ask, "ask")?;
+    }
+
+    for item in &position.offer {
+        if let OtcItemInfo::Cw721 { .. } = item.item_info {
+            let asset_key = item.item_info.asset_key();
+
+            if position
+                .ask
+                .iter()
+                .any(|val| val.item_info.asset_key() == asset_key)
+            {
+                return Err(ContractError::DuplicateAsset { asset: asset_key });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Normalise the items of the legs of a new ring trade and apply the checks not covered by `RingTrade::validate`
+pub fn normalize_and_validate_ring_trade(
+    config: &Config,
+    ring_trade: &mut RingTrade,
+) -> Result<(), ContractError> {
+    for leg in ring_trade.legs.iter_mut() {
+        leg.items = normalize_otc_items(leg.items.clone())?;
+
+        validate_otc_items(config, &leg.items, "leg")?;
+    }
+
+    Ok(())
+}
+
+/// Check the secret revealed for a hash locked position against the hash lock and the
+/// commitment done by `sender` in a previous block, consuming the commitment
+pub fn verify_hash_lock(
+    storage: &mut dyn Storage,
+    env: &Env,
+    position: &OtcPosition,
+    sender: &Addr,
+    secret: Option<Binary>,
+) -> Result<(), ContractError> {
+    let Some(hash_lock) = &position.hash_lock else {
+        return Ok(());
+    };
+
+    let secret = secret.ok_or(ContractError::InvalidSecret {})?;
+
+    if Sha256::digest(secret.as_slice())[..] != hash_lock[..] {
+        return Err(ContractError::InvalidSecret {});
+    }
+
+    let commitment = COMMITMENTS
+        .may_load(storage, (position.id, sender))?
+        .ok_or(ContractError::MissingCommitment {})?;
+
+    if commitment.height >= env.block.height {
+        return Err(ContractError::MissingCommitment {});
+    }
+
+    let expected = Sha256::new()
+        .chain_update(secret.as_slice())
+        .chain_update(sender.as_bytes())
+        .finalize();
+
+    if expected[..] != commitment.commitment[..] {
+        return Err(ContractError::InvalidSecret {});
+    }
+
+    COMMITMENTS.remove(storage, (position.id, sender));
 
-use crate::state::positions;
+    Ok(())
+}
+
+/// Verify the signature of the order and that `pubkey` belongs to the maker.
+///
+/// The maker address is derived from the pubkey as in the Cosmos SDK, using the bech32 prefix of the maker
+pub fn verify_signed_order(
+    deps: Deps,
+    env: &Env,
+    order: &SignedOrder,
+    signature: &Binary,
+    pubkey: &Binary,
+) -> Result<(), ContractError> {
+    let payload = SignedOrderPayload {
+        chain_id: env.block.chain_id.clone(),
+        contract: env.contract.address.to_string(),
+        order: order.clone(),
+    }
+    .into_binary()?;
+
+    if pubkey.len() != 33 {
+        return Err(StdError::generic_err("Pubkey must be compressed").into());
+    }
+
+    let verified = deps
+        .api
+        .secp256k1_verify(&Sha256::digest(payload.as_slice()), signature, pubkey)
+        .map_err(|_| ContractError::InvalidSignature {})?;
+
+    if !verified {
+        return Err(ContractError::InvalidSignature {});
+    }
+
+    let (prefix, _, _) = bech32::decode(&order.maker)
+        .map_err(|_| StdError::generic_err(format!("Invalid maker address: {}", order.maker)))?;
+
+    let pubkey_address = bech32::encode(
+        &prefix,
+        Ripemd160::digest(Sha256::digest(pubkey.as_slice())).to_base32(),
+        Variant::Bech32,
+    )
+    .map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    if pubkey_address != order.maker {
+        return Err(ContractError::InvalidSignature {});
+    }
+
+    Ok(())
+}
 
 pub fn collect_otc_items(
+    storage: &mut dyn Storage,
     env: &Env,
     items: &Vec<OtcItem>,
     sender: Addr,
@@ -15,54 +240,645 @@ pub fn collect_otc_items(
         &items.iter().map(|val| val.item_info.clone()).collect(),
         funds,
     )?;
+
+    Ok((pull_otc_items(storage, env, items, &sender)?, coins))
+}
+
+/// Like `collect_otc_items`, for items without vesting and pricing
+pub fn collect_otc_info_items(
+    storage: &mut dyn Storage,
+    env: &Env,
+    items_info: &Vec<OtcItemInfo>,
+    sender: &Addr,
+    funds: Vec<Coin>,
+) -> StdResult<(Vec<CosmosMsg>, Vec<Coin>)> {
+    let coins = assert_received_funds(items_info, funds)?;
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    for item_info in items_info {
+        increase_escrow(storage, item_info, item_info.get_amount())?;
+
+        if !matches!(item_info, OtcItemInfo::Token { .. }) {
+            msgs.push(item_info.build_send_msg(env, sender, &env.contract.address, None)?);
+        }
+    }
+
+    Ok((msgs, coins))
+}
+
+/// Pull the Cw20 / Cw721 items from `sender` and register all the items in the escrow ledger,
+/// assuming the native items already received
+pub fn pull_otc_items(
+    storage: &mut dyn Storage,
+    env: &Env,
+    items: &Vec<OtcItem>,
+    sender: &Addr,
+) -> StdResult<Vec<CosmosMsg>> {
     let mut msgs: Vec<CosmosMsg> = vec![];
     for item in items {
+        increase_escrow(storage, &item.item_info, item.item_info.get_amount())?;
+
         match &item.item_info {
             OtcItemInfo::Cw20 { .. } | &OtcItemInfo::Cw721 { .. } => msgs.push(
                 item.item_info
-                    .build_send_msg(env, &sender, &env.contract.address, None)?,
+                    .build_send_msg(env, sender, &env.contract.address, None)?,
             ),
             _ => {}
         }
     }
 
-    Ok((msgs, coins))
+    Ok(msgs)
+}
+
+/// Load a position for `ExecuteOtc` / `ExecuteMany`, activating it for `sender` and fixing the ask price
+pub fn prepare_execute_otc(
+    deps: DepsMut,
+    env: &Env,
+    config: &Config,
+    sender: &Addr,
+    id: u64,
+    secret: Option<Binary>,
+) -> Result<OtcPosition, ContractError> {
+    let mut position = positions().load(deps.storage, id)?;
+
+    if position.auction.is_some() {
+        return Err(StdError::generic_err("Auction positions must be settled").into());
+    }
+
+    assert_not_disputed(&position)?;
+
+    if position.is_expired(env) {
+        return Err(StdError::generic_err("Position expired").into());
+    }
+
+    position.active(env, sender)?;
+
+    verify_hash_lock(deps.storage, env, &position, sender, secret)?;
+
+    position.resolve_ask_pricing(deps.as_ref(), env, config.price_oracle.as_ref())?;
+
+    Ok(position)
+}
+
+/// Items to be paid by the executor of a position: the `ask` and the fee
+pub fn execute_otc_required_items(config: &Config, position: &OtcPosition) -> Vec<OtcItemInfo> {
+    position
+        .ask
+        .iter()
+        .map(|val| val.item_info.clone())
+        .chain(config.fee.clone())
+        .collect()
+}
+
+/// Settle a position prepared by `prepare_execute_otc`, assuming the native funds already received
+pub fn execute_otc(
+    deps: DepsMut,
+    env: &Env,
+    config: &Config,
+    sender: &Addr,
+    position: &mut OtcPosition,
+    referrer: Option<&Addr>,
+) -> StdResult<(Vec<CosmosMsg>, Vec<SubMsg>, Vec<Attribute>)> {
+    record_execution(deps.storage, env, position)?;
+
+    let mut msgs = pull_otc_items(deps.storage, env, &position.ask, sender)?;
+
+    msgs.extend(build_fee_msgs(deps.storage, env, config, sender, referrer)?);
+
+    msgs.extend(send_otc_items(
+        deps.storage,
+        env,
+        &mut position.ask,
+        &position.status,
+        position.milestones.as_ref(),
+        &position.owner,
+    )?);
+    msgs.extend(send_otc_items(
+        deps.storage,
+        env,
+        &mut position.offer,
+        &position.status,
+        position.milestones.as_ref(),
+        sender,
+    )?);
+
+    msgs.extend(refund_bids(deps.storage, env, position.id)?);
+
+    msgs.extend(release_bond(deps.storage, env, position, false)?);
+
+    let msgs_hook = build_hook_msgs(deps.storage, position, executed_hook_msg(position))?;
+
+    let attrs_close = after_action(deps, env, position)?;
+
+    Ok((msgs, msgs_hook, attrs_close))
 }
 
 pub fn send_otc_items(
+    storage: &mut dyn Storage,
     env: &Env,
     items: &mut Vec<OtcItem>,
     position_status: &OtcPositionStatus,
+    milestones: Option<&MilestonePlan>,
     to: &Addr,
 ) -> StdResult<Vec<CosmosMsg>> {
     let mut msgs: Vec<CosmosMsg> = vec![];
     for item in items {
-        let amount = item.sendable_amount_and_update_claimed_amount(env, position_status)?;
+        msgs.extend(send_otc_item(
+            storage,
+            env,
+            item,
+            position_status,
+            milestones,
+            to,
+        )?);
+    }
+    Ok(msgs)
+}
 
-        if amount > Uint128::zero() {
-            msgs.push(item.item_info.build_send_msg(
-                env,
-                &env.contract.address,
-                to,
-                Some(amount),
-            )?)
+/// Send the vested amount of the items with a vesting.
+///
+/// Items without vesting are fully sent when the position is activated and are skipped
+pub fn claim_otc_items(
+    storage: &mut dyn Storage,
+    env: &Env,
+    items: &mut [OtcItem],
+    position_status: &OtcPositionStatus,
+    milestones: Option<&MilestonePlan>,
+    to: &Addr,
+) -> StdResult<Vec<CosmosMsg>> {
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    for item in items.iter_mut().filter(|val| val.vesting_info.is_some()) {
+        msgs.extend(send_otc_item(
+            storage,
+            env,
+            item,
+            position_status,
+            milestones,
+            to,
+        )?);
+    }
+    Ok(msgs)
+}
+
+/// Return the unclaimed amount of the items with a vesting to `to`, the depositor of the items
+pub fn unwind_otc_items(
+    storage: &mut dyn Storage,
+    env: &Env,
+    items: &mut [OtcItem],
+    to: &Addr,
+) -> StdResult<Vec<CosmosMsg>> {
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    for item in items.iter_mut() {
+        if let Some(vesting_info) = &mut item.vesting_info {
+            let amount = item.item_info.get_amount() - vesting_info.claimed;
+
+            if amount > Uint128::zero() {
+                vesting_info.claimed += amount;
+
+                decrease_escrow(storage, &item.item_info, amount)?;
+
+                msgs.push(item.item_info.build_send_msg(
+                    env,
+                    &env.contract.address,
+                    to,
+                    Some(amount),
+                )?);
+            }
+        }
+    }
+    Ok(msgs)
+}
+
+/// Split the unclaimed amount of the items between the depositor and the counterparty,
+/// sending `settle_share` to the counterparty after paying the arbiter fee.
+///
+/// In `Pending` all the items are unclaimed, otherwise only the unclaimed amount of the vesting items
+pub fn resolve_otc_items(
+    storage: &mut dyn Storage,
+    env: &Env,
+    items: &mut [OtcItem],
+    position_status: &OtcPositionStatus,
+    arbiter: &Arbiter,
+    settle_share: Decimal,
+    (depositor, counterparty): (&Addr, &Addr),
+) -> StdResult<Vec<CosmosMsg>> {
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    for item in items.iter_mut() {
+        let amount = item.item_info.get_amount();
+
+        let unclaimed = match &mut item.vesting_info {
+            _ if position_status.is_in_pending() => amount,
+            Some(vesting_info) => {
+                let unclaimed = amount - vesting_info.claimed;
+                vesting_info.claimed = amount;
+                unclaimed
+            }
+            None => Uint128::zero(),
+        };
+
+        if unclaimed.is_zero() {
+            continue;
+        }
+
+        decrease_escrow(storage, &item.item_info, unclaimed)?;
+
+        let mut shares: Vec<(&Addr, Uint128)> = vec![];
+
+        if let OtcItemInfo::Cw721 { .. } = item.item_info {
+            if settle_share == Decimal::one() {
+                shares.push((counterparty, unclaimed));
+            } else if settle_share.is_zero() {
+                shares.push((depositor, unclaimed));
+            } else {
+                return Err(StdError::generic_err(
+                    "Cw721 can't be split by a pro-rata resolution",
+                ));
+            }
+        } else {
+            let arbiter_fee = unclaimed * arbiter.fee;
+            let to_counterparty = (unclaimed - arbiter_fee) * settle_share;
+
+            shares.push((&arbiter.address, arbiter_fee));
+            shares.push((counterparty, to_counterparty));
+            shares.push((depositor, unclaimed - arbiter_fee - to_counterparty));
+        }
+
+        for (to, amount) in shares {
+            if !amount.is_zero() {
+                msgs.push(item.item_info.build_send_msg(
+                    env,
+                    &env.contract.address,
+                    to,
+                    Some(amount),
+                )?);
+            }
         }
     }
     Ok(msgs)
 }
 
+pub fn assert_not_disputed(position: &OtcPosition) -> StdResult<()> {
+    if position.dispute.is_some() {
+        return Err(StdError::generic_err("Position is under dispute"));
+    }
+
+    Ok(())
+}
+
+fn send_otc_item(
+    storage: &mut dyn Storage,
+    env: &Env,
+    item: &mut OtcItem,
+    position_status: &OtcPositionStatus,
+    milestones: Option<&MilestonePlan>,
+    to: &Addr,
+) -> StdResult<Option<CosmosMsg>> {
+    let amount =
+        item.sendable_amount_and_update_claimed_amount(env, position_status, milestones)?;
+
+    if amount > Uint128::zero() {
+        decrease_escrow(storage, &item.item_info, amount)?;
+
+        Ok(Some(item.item_info.build_send_msg(
+            env,
+            &env.contract.address,
+            to,
+            Some(amount),
+        )?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Send the fee to the `fee_collectors`, sharing `Config.referral_share` with the referrer.
+///
+/// Native fees are taken from `funds`, cw20 fees are pulled from the `payer` allowance
+/// directly to the recipients, so the escrowed balances are never used to pay fees
 pub fn send_fee(
+    storage: &mut dyn Storage,
     env: &Env,
-    items_info: &Vec<OtcItemInfo>,
-    fee_collector: &Addr,
+    config: &Config,
+    payer: &Addr,
+    funds: Vec<Coin>,
+    referrer: Option<&Addr>,
+) -> StdResult<(Vec<CosmosMsg>, Vec<Coin>)> {
+    let remaining_coins = assert_received_funds(&config.fee, funds)?;
+
+    Ok((
+        build_fee_msgs(storage, env, config, payer, referrer)?,
+        remaining_coins,
+    ))
+}
+
+/// Build the fee messages of `send_fee`, assuming the native fees already received
+pub fn build_fee_msgs(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    payer: &Addr,
+    referrer: Option<&Addr>,
+) -> StdResult<Vec<CosmosMsg>> {
+    build_fee_items_msgs(storage, env, config, &config.fee, payer, referrer)
+}
+
+/// Send a fee escrowed by the contract, like the fee of a bid, to the `fee_collectors`
+pub fn build_escrowed_fee_msgs(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    fee: &[OtcItemInfo],
+) -> StdResult<Vec<CosmosMsg>> {
+    for item_info in fee {
+        decrease_escrow(storage, item_info, item_info.get_amount())?;
+    }
+
+    build_fee_items_msgs(storage, env, config, fee, &env.contract.address, None)
+}
+
+fn build_fee_items_msgs(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    fee: &[OtcItemInfo],
+    payer: &Addr,
+    referrer: Option<&Addr>,
+) -> StdResult<Vec<CosmosMsg>> {
+    let mut msgs: Vec<CosmosMsg> = vec![];
+
+    for item_info in fee {
+        record_fee(storage, env, item_info, item_info.get_amount())?;
+
+        let from = match item_info {
+            OtcItemInfo::Token { .. } => &env.contract.address,
+            OtcItemInfo::Cw20 { .. } => payer,
+            OtcItemInfo::Cw721 { .. } => {
+                return Err(StdError::generic_err("Cw721 can't be used as fee"))
+            }
+        };
+
+        let referral_amount = match referrer {
+            Some(_) => item_info.get_amount() * config.referral_share,
+            None => Uint128::zero(),
+        };
+
+        if !referral_amount.is_zero() {
+            let referrer = referrer.unwrap();
+
+            msgs.push(item_info.build_send_msg(env, from, referrer, Some(referral_amount))?);
+
+            REFERRAL_TOTALS.update(
+                storage,
+                (referrer, item_info.asset_key()),
+                |total| -> StdResult<_> {
+                    match total {
+                        Some(total) => total.with_amount(total.get_amount() + referral_amount),
+                        None => item_info.with_amount(referral_amount),
+                    }
+                },
+            )?;
+        }
+
+        let collectors_amount = item_info.get_amount() - referral_amount;
+
+        if !collectors_amount.is_zero() {
+            msgs.extend(build_fee_collectors_msgs(
+                env,
+                config,
+                item_info,
+                from,
+                collectors_amount,
+            )?);
+        }
+    }
+
+    Ok(msgs)
+}
+
+fn update_daily_stats(
+    storage: &mut dyn Storage,
+    env: &Env,
+    action: impl FnOnce(&mut DailyStats),
+) -> StdResult<()> {
+    let day = env.block.time.seconds() / SECONDS_PER_DAY;
+
+    let mut daily_stats = DAILY_STATS
+        .may_load(storage, day)?
+        .unwrap_or(DailyStats::new(day));
+
+    action(&mut daily_stats);
+
+    DAILY_STATS.save(storage, day, &daily_stats)
+}
+
+pub fn record_creation(storage: &mut dyn Storage, env: &Env) -> StdResult<()> {
+    update_daily_stats(storage, env, |stats| stats.created += 1)
+}
+
+/// Add the items of an executed position to the traded volume
+pub fn record_execution(
+    storage: &mut dyn Storage,
+    env: &Env,
+    position: &OtcPosition,
+) -> StdResult<()> {
+    let executor = position
+        .executor
+        .as_ref()
+        .ok_or(StdError::generic_err("Position has no executor"))?;
+
+    record_trade(
+        storage,
+        env,
+        position
+            .offer
+            .iter()
+            .chain(position.ask.iter())
+            .map(|val| &val.item_info)
+            .collect(),
+        vec![&position.owner, executor],
+    )
+}
+
+/// Add the traded items to the volume and the parties to the traders
+pub fn record_trade(
+    storage: &mut dyn Storage,
+    env: &Env,
+    items: Vec<&OtcItemInfo>,
+    traders: Vec<&Addr>,
+) -> StdResult<()> {
+    let mut new_traders = 0;
+
+    for trader in traders {
+        if !TRADERS.has(storage, trader) {
+            TRADERS.save(storage, trader, &Empty {})?;
+            new_traders += 1;
+        }
+    }
+
+    let traders_count = TRADERS_COUNT.may_load(storage)?.unwrap_or_default();
+    TRADERS_COUNT.save(storage, &(traders_count + new_traders))?;
+
+    for item_info in &items {
+        VOLUMES.update(storage, item_info.asset_class(), |volume| -> StdResult<_> {
+            Ok(volume.unwrap_or_default() + item_info.get_amount())
+        })?;
+    }
+
+    update_daily_stats(storage, env, |stats| {
+        stats.executed += 1;
+        stats.new_traders += new_traders;
+
+        for item_info in items {
+            AssetAmount::add(&mut stats.volume, item_info, item_info.get_amount());
+        }
+    })
+}
+
+pub fn record_fee(
+    storage: &mut dyn Storage,
+    env: &Env,
+    item_info: &OtcItemInfo,
+    amount: Uint128,
+) -> StdResult<()> {
+    FEES.update(storage, item_info.asset_class(), |fees| -> StdResult<_> {
+        Ok(fees.unwrap_or_default() + amount)
+    })?;
+
+    update_daily_stats(storage, env, |stats| {
+        AssetAmount::add(&mut stats.fees, item_info, amount)
+    })
+}
+
+/// Distribute `amount` of a fungible fee item, owned by `from`, between the `fee_collectors` by weight
+pub fn build_fee_collectors_msgs(
+    env: &Env,
+    config: &Config,
+    item_info: &OtcItemInfo,
+    from: &Addr,
+    amount: Uint128,
+) -> StdResult<Vec<CosmosMsg>> {
+    if let OtcItemInfo::Cw721 { .. } = item_info {
+        return Err(StdError::generic_err("Cw721 can't be used as fee"));
+    }
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+
+    for (fee_collector, share) in FeeCollector::split(&config.fee_collectors, amount) {
+        if !share.is_zero() {
+            msgs.push(item_info.build_send_msg(env, from, &fee_collector, Some(share))?);
+        }
+    }
+
+    Ok(msgs)
+}
+
+pub fn validate_referrer(
+    deps: Deps,
+    referrer: Option<String>,
+    sender: &Addr,
+) -> StdResult<Option<Addr>> {
+    let referrer = referrer.map(|val| val.into_addr(deps.api)).transpose()?;
+
+    if referrer.as_ref() == Some(sender) {
+        return Err(StdError::generic_err("Referrer can't be the sender"));
+    }
+
+    Ok(referrer)
+}
+
+/// Take the `Config.listing_bond` from the owner of a new position, if any.
+///
+/// Native bonds are taken from `funds`, cw20 bonds are pulled from the owner allowance
+pub fn collect_listing_bond(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    position: &mut OtcPosition,
     funds: Vec<Coin>,
+) -> StdResult<(Vec<CosmosMsg>, Vec<Coin>)> {
+    let Some(listing_bond) = &config.listing_bond else {
+        return Ok((vec![], funds));
+    };
+
+    if position.auction.is_some() {
+        return Ok((vec![], funds));
+    }
+
+    let remaining_coins = assert_received_funds(&vec![listing_bond.asset.clone()], funds)?;
+
+    increase_escrow(
+        storage,
+        &listing_bond.asset,
+        listing_bond.asset.get_amount(),
+    )?;
+
+    let msgs = if let OtcItemInfo::Cw20 { .. } = listing_bond.asset {
+        vec![listing_bond.asset.build_send_msg(
+            env,
+            &position.owner,
+            &env.contract.address,
+            None,
+        )?]
+    } else {
+        vec![]
+    };
+
+    position.bond = Some(PositionBond {
+        asset: listing_bond.asset.clone(),
+        expiry: env.block.time.seconds() + listing_bond.duration,
+        status: BondStatus::Locked,
+    });
+
+    Ok((msgs, remaining_coins))
+}
+
+/// Return the locked listing bond of a position to the owner, or send it to the `fee_collectors` if `slash`
+pub fn release_bond(
+    storage: &mut dyn Storage,
+    env: &Env,
+    position: &mut OtcPosition,
+    slash: bool,
 ) -> StdResult<Vec<CosmosMsg>> {
-    assert_received_funds(items_info, funds)?;
-    build_send_otc_info_items(env, items_info, fee_collector)
+    let Some(bond) = position
+        .bond
+        .as_mut()
+        .filter(|val| val.status == BondStatus::Locked)
+    else {
+        return Ok(vec![]);
+    };
+
+    decrease_escrow(storage, &bond.asset, bond.asset.get_amount())?;
+
+    if slash {
+        bond.status = BondStatus::Slashed;
+
+        record_fee(storage, env, &bond.asset, bond.asset.get_amount())?;
+
+        build_fee_collectors_msgs(
+            env,
+            &CONFIG.load(storage)?,
+            &bond.asset,
+            &env.contract.address,
+            bond.asset.get_amount(),
+        )
+    } else {
+        bond.status = BondStatus::Returned;
+
+        Ok(vec![bond.asset.build_send_msg(
+            env,
+            &env.contract.address,
+            &position.owner,
+            None,
+        )?])
+    }
 }
 
-pub fn cancel_otc(env: &Env, position: &OtcPosition) -> StdResult<Vec<CosmosMsg>> {
+pub fn cancel_otc(
+    storage: &mut dyn Storage,
+    env: &Env,
+    position: &OtcPosition,
+) -> StdResult<Vec<CosmosMsg>> {
     build_send_otc_info_items(
+        storage,
         env,
         &position
             .offer
@@ -73,13 +889,103 @@ pub fn cancel_otc(env: &Env, position: &OtcPosition) -> StdResult<Vec<CosmosMsg>
     )
 }
 
+/// Refund and remove all the open bids of a position
+pub fn refund_bids(
+    storage: &mut dyn Storage,
+    env: &Env,
+    position_id: u64,
+) -> StdResult<Vec<CosmosMsg>> {
+    let bids = BIDS
+        .prefix(position_id)
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+
+    for (bid_id, bid) in bids {
+        msgs.extend(build_send_otc_info_items(
+            storage,
+            env,
+            &bid.escrowed_items(),
+            &bid.bidder,
+        )?);
+
+        BIDS.remove(storage, (position_id, bid_id));
+    }
+
+    Ok(msgs)
+}
+
+/// Build the messages for the registered hooks and the hook of the position.
+///
+/// With `HookPolicy::Ignore` the messages are dispatched as submessages replying on error
+pub fn build_hook_msgs(
+    storage: &dyn Storage,
+    position: &OtcPosition,
+    hook_msg: OtcHookMsg,
+) -> StdResult<Vec<SubMsg>> {
+    let config = CONFIG.load(storage)?;
+
+    let hooks = HOOKS
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<Addr>>>()?;
+
+    let build_msg = |hook: Addr| -> StdResult<CosmosMsg> {
+        Ok(
+            WasmMsg::build_execute(hook, OtcHookExecuteMsg::OtcHook(hook_msg.clone()), vec![])?
+                .into(),
+        )
+    };
+
+    let mut msgs = vec![];
+
+    for hook in &hooks {
+        let msg = build_msg(hook.clone())?;
+
+        msgs.push(match config.hook_policy {
+            HookPolicy::Revert => SubMsg::new(msg),
+            HookPolicy::Ignore => SubMsg::reply_on_error(msg, REPLY_ID_HOOK),
+        });
+    }
+
+    // The hook of the position is chosen by its owner: its failure must never
+    // block the other parties, so it's always ignored
+    if let Some(hook) = &position.hook {
+        if !hooks.contains(hook) {
+            msgs.push(SubMsg::reply_on_error(
+                build_msg(hook.clone())?,
+                REPLY_ID_HOOK,
+            ));
+        }
+    }
+
+    Ok(msgs)
+}
+
+pub fn executed_hook_msg(position: &OtcPosition) -> OtcHookMsg {
+    OtcHookMsg::Executed {
+        id: position.id,
+        owner: position.owner.clone(),
+        executor: position.executor.clone().unwrap(),
+    }
+}
+
+pub fn cancelled_hook_msg(position: &OtcPosition) -> OtcHookMsg {
+    OtcHookMsg::Cancelled {
+        id: position.id,
+        owner: position.owner.clone(),
+    }
+}
+
 pub fn build_send_otc_info_items(
+    storage: &mut dyn Storage,
     env: &Env,
     items_info: &Vec<OtcItemInfo>,
     to: &Addr,
 ) -> StdResult<Vec<CosmosMsg>> {
     let mut msgs: Vec<CosmosMsg> = vec![];
     for item_info in items_info {
+        decrease_escrow(storage, item_info, item_info.get_amount())?;
         msgs.push(item_info.build_send_msg(env, &env.contract.address, to, None)?)
     }
     Ok(msgs)
@@ -96,7 +1002,7 @@ pub fn assert_received_funds(items: &Vec<OtcItemInfo>, funds: Vec<Coin>) -> StdR
 
             if amount > available_amount {
                 return Err(StdError::generic_err(format!(
-                    "Amount received for {denom} is to low: expected: {amount}, received: {amount}"
+                    "Amount received for {denom} is to low: expected: {amount}, received: {available_amount}"
                 )));
             }
 
@@ -106,10 +1012,150 @@ pub fn assert_received_funds(items: &Vec<OtcItemInfo>, funds: Vec<Coin>) -> StdR
 
     Ok(coins
         .into_iter()
+        .filter(|(_, amount)| !amount.is_zero())
         .map(|(denom, amount)| Coin::new(amount.u128(), denom))
         .collect())
 }
 
+/// Refund or reject the coins not used by the action, according to `Config.excess_funds_policy`
+/// Apply `Config.excess_funds_policy` to the funds sent to an action that doesn't require any
+pub fn handle_unexpected_funds(
+    storage: &dyn Storage,
+    info: &MessageInfo,
+) -> StdResult<Vec<CosmosMsg>> {
+    if info.funds.is_empty() {
+        return Ok(vec![]);
+    }
+
+    handle_excess_funds(&CONFIG.load(storage)?, &info.sender, info.funds.clone())
+}
+
+pub fn handle_excess_funds(
+    config: &Config,
+    sender: &Addr,
+    remaining_coins: Vec<Coin>,
+) -> StdResult<Vec<CosmosMsg>> {
+    if remaining_coins.is_empty() {
+        return Ok(vec![]);
+    }
+
+    match config.excess_funds_policy {
+        ExcessFundsPolicy::Refund => Ok(vec![BankMsg::Send {
+            to_address: sender.to_string(),
+            amount: remaining_coins,
+        }
+        .into()]),
+        ExcessFundsPolicy::Reject => Err(StdError::generic_err(format!(
+            "Unexpected funds received: {}",
+            remaining_coins
+                .iter()
+                .map(|val| val.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        ))),
+    }
+}
+
+/// Amount of `asset` held by the contract on behalf of the positions and the bids
+pub fn escrowed_amount(storage: &dyn Storage, asset: &OtcItemInfo) -> StdResult<Uint128> {
+    Ok(ESCROW
+        .may_load(storage, asset.asset_key())?
+        .map(|val| val.get_amount())
+        .unwrap_or_default())
+}
+
+/// Register `amount` of `item_info` as held by the contract in the escrow ledger
+pub fn increase_escrow(
+    storage: &mut dyn Storage,
+    item_info: &OtcItemInfo,
+    amount: Uint128,
+) -> StdResult<()> {
+    ESCROW.update(storage, item_info.asset_key(), |escrow| match escrow {
+        Some(escrow) => escrow.with_amount(escrow.get_amount() + amount),
+        None => match item_info {
+            OtcItemInfo::Cw721 { .. } => Ok(item_info.clone()),
+            _ => item_info.with_amount(amount),
+        },
+    })?;
+
+    Ok(())
+}
+
+/// Remove `amount` of `item_info` from the escrow ledger when it leaves the contract
+pub fn decrease_escrow(
+    storage: &mut dyn Storage,
+    item_info: &OtcItemInfo,
+    amount: Uint128,
+) -> StdResult<()> {
+    let asset_key = item_info.asset_key();
+
+    let escrow = ESCROW
+        .may_load(storage, asset_key.clone())?
+        .ok_or(StdError::generic_err(format!(
+            "Asset not escrowed: {asset_key}"
+        )))?;
+
+    let remaining = escrow.get_amount().checked_sub(amount)?;
+
+    if remaining.is_zero() {
+        ESCROW.remove(storage, asset_key);
+    } else {
+        ESCROW.save(storage, asset_key, &escrow.with_amount(remaining)?)?;
+    }
+
+    Ok(())
+}
+
+/// Register in the escrow ledger the funds of the positions and bids stored before it existed:
+/// the unclaimed `offer` of the pending positions and the unclaimed vesting items of both sides
+/// of the vesting positions
+pub fn rebuild_escrow(storage: &mut dyn Storage) -> StdResult<()> {
+    let stored_positions = positions()
+        .range(storage, None, None, Order::Ascending)
+        .map(|val| val.map(|(_, position)| position))
+        .collect::<StdResult<Vec<OtcPosition>>>()?;
+
+    for position in stored_positions {
+        let items: Vec<&OtcItem> = match position.status {
+            OtcPositionStatus::Pending => position.offer.iter().collect(),
+            OtcPositionStatus::Vesting(..) => position
+                .offer
+                .iter()
+                .chain(position.ask.iter())
+                .filter(|item| item.vesting_info.is_some())
+                .collect(),
+            _ => vec![],
+        };
+
+        for item in items {
+            let claimed = item
+                .vesting_info
+                .as_ref()
+                .map(|val| val.claimed)
+                .unwrap_or_default();
+
+            let unclaimed = item.item_info.get_amount() - claimed;
+
+            if !unclaimed.is_zero() {
+                increase_escrow(storage, &item.item_info, unclaimed)?;
+            }
+        }
+    }
+
+    let bids = BIDS
+        .range(storage, None, None, Order::Ascending)
+        .map(|val| val.map(|(_, bid)| bid))
+        .collect::<StdResult<Vec<OtcBid>>>()?;
+
+    for bid in bids {
+        for item_info in bid.escrowed_items() {
+            increase_escrow(storage, &item_info, item_info.get_amount())?;
+        }
+    }
+
+    Ok(())
+}
+
 pub fn after_action(
     deps: DepsMut,
     env: &Env,
@@ -122,7 +1168,9 @@ pub fn after_action(
     let mut attributes: Vec<Attribute> = vec![];
 
     match position.status {
-        OtcPositionStatus::Pending => {
+        OtcPositionStatus::Pending
+        | OtcPositionStatus::Unwound(_)
+        | OtcPositionStatus::Arbitrated(_) => {
             return Err(StdError::generic_err(
                 "Position should be Executed or Vesting",
             ))
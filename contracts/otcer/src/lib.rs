@@ -3,5 +3,5 @@ pub mod contract;
 mod execute;
 mod functions;
 mod query;
-mod response;
+pub mod response;
 mod state;
@@ -1,17 +1,43 @@
-use cosmwasm_std::{Deps, Order, StdError, StdResult};
+use std::cmp::min;
+
+use cosmwasm_std::{Addr, Deps, Env, Order, StdError, StdResult, Timestamp, Uint128};
+use cw2::get_contract_version;
+use cw_storage_plus::{Bound, Map};
 use otcer_pkg::otcer::{
-    definitions::OtcPosition,
-    msgs::{QueryPositionsFilter, QueryPositionsFilterOrder},
+    definitions::{
+        AssetAmount, DailyStats, OtcBid, OtcItem, OtcItemInfo, OtcPosition, OtcPositionStatus,
+        RingTrade,
+    },
+    msgs::{
+        AssetSolvency, ClaimableItem, ConfigResponse, ContractInfoResponse, QueryPositionsFilter,
+        QueryPositionsFilterOrder, SolvencyResponse, StatsResponse, StatusCount,
+        VestingTimelineResponse,
+    },
+};
+use rhaki_cw_plus::traits::IntoAddr;
+
+use crate::state::{
+    positions, BIDS, CONFIG, DAILY_STATS, ESCROW, FEES, HOOKS, NONCES, REFERRAL_TOTALS,
+    RING_TRADES, STATUS_COUNTS, TRADERS_COUNT, VOLUMES,
 };
 
-use crate::state::positions;
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
 
-pub fn qy_position(deps: Deps, id: u64) -> StdResult<OtcPosition> {
-    positions().load(deps.storage, id)
+pub fn qy_position(deps: Deps, env: Env, id: u64) -> StdResult<OtcPosition> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(with_current_price(
+        deps,
+        &env,
+        config.price_oracle.as_ref(),
+        positions().load(deps.storage, id)?,
+    ))
 }
 
 pub fn qy_positions(
     deps: Deps,
+    env: Env,
     start_after: Option<u64>,
     limit: Option<u32>,
     filters: Option<QueryPositionsFilter>,
@@ -21,7 +47,24 @@ pub fn qy_positions(
         .unwrap_or(QueryPositionsFilterOrder::Descending)
         .into();
     if let Some(filters) = filters {
+        let asset_filter = filters.offer_asset.is_some() || filters.ask_asset.is_some();
+
         match (filters.owner, filters.executor, filters.status) {
+            // offer_asset-ask_asset-status
+            (None, None, status) if asset_filter => positions_by_asset(
+                deps,
+                filters.offer_asset,
+                filters.ask_asset,
+                status.map(|val| val.as_string()),
+                order,
+                start_after,
+                limit,
+            ),
+            _ if asset_filter => {
+                return Err(StdError::generic_err(
+                    "Asset filters can be combined only with status",
+                ))
+            }
             (None, None, None) => return Err(StdError::generic_err("None filter provided")),
             // status
             (None, None, Some(status)) => {
@@ -110,5 +153,295 @@ pub fn qy_positions(
             start_after,
         )
     }
-    .map(|val| val.into_iter().map(|(_, val)| val).collect())
+    .and_then(|val| {
+        let config = CONFIG.load(deps.storage)?;
+
+        Ok(val
+            .into_iter()
+            .map(|(_, val)| with_current_price(deps, &env, config.price_oracle.as_ref(), val))
+            .collect())
+    })
+}
+
+fn positions_by_asset(
+    deps: Deps,
+    offer_asset: Option<String>,
+    ask_asset: Option<String>,
+    status: Option<String>,
+    order: Order,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(u64, OtcPosition)>> {
+    let idx = positions().idx;
+    let limit = min(limit.unwrap_or(DEFAULT_LIMIT), MAX_LIMIT) as usize;
+
+    let ids = match (offer_asset, ask_asset, status) {
+        (Some(offer), None, None) => idx
+            .offer
+            .ids(deps.storage, offer, order, start_after, limit),
+        (Some(offer), None, Some(status)) => {
+            idx.offer_status
+                .ids(deps.storage, (offer, status), order, start_after, limit)
+        }
+        (None, Some(ask), None) => idx.ask.ids(deps.storage, ask, order, start_after, limit),
+        (None, Some(ask), Some(status)) => {
+            idx.ask_status
+                .ids(deps.storage, (ask, status), order, start_after, limit)
+        }
+        (Some(offer), Some(ask), None) => {
+            idx.pair
+                .ids(deps.storage, (offer, ask), order, start_after, limit)
+        }
+        (Some(offer), Some(ask), Some(status)) => idx.pair_status.ids(
+            deps.storage,
+            (offer, ask, status),
+            order,
+            start_after,
+            limit,
+        ),
+        (None, None, _) => return Err(StdError::generic_err("None asset filter provided")),
+    }?;
+
+    ids.into_iter()
+        .map(|id| Ok((id, positions().load(deps.storage, id)?)))
+        .collect()
+}
+
+pub fn qy_bids(
+    deps: Deps,
+    id: u64,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<OtcBid>> {
+    BIDS.prefix(id)
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(min(limit.unwrap_or(DEFAULT_LIMIT), MAX_LIMIT) as usize)
+        .map(|val| val.map(|(_, val)| val))
+        .collect()
+}
+
+pub fn qy_hooks(deps: Deps) -> StdResult<Vec<Addr>> {
+    HOOKS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect()
+}
+
+pub fn qy_referral_totals(deps: Deps, referrer: String) -> StdResult<Vec<OtcItemInfo>> {
+    let referrer = referrer.into_addr(deps.api)?;
+
+    REFERRAL_TOTALS
+        .prefix(&referrer)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|val| val.map(|(_, val)| val))
+        .collect()
+}
+
+/// Report the `current_amount` of the ask items with pricing of pending positions.
+/// A failing price source must not break the query: the `current_amount` is left to `None`
+fn with_current_price(
+    deps: Deps,
+    env: &Env,
+    price_oracle: Option<&Addr>,
+    mut position: OtcPosition,
+) -> OtcPosition {
+    if position.status.is_in_pending() {
+        for item in position
+            .ask
+            .iter_mut()
+            .filter(|item| item.pricing.is_some())
+        {
+            item.current_amount = item.current_amount(deps, env, price_oracle).ok();
+        }
+    }
+
+    position
+}
+
+pub fn qy_solvency(
+    deps: Deps,
+    env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<SolvencyResponse> {
+    let assets = ESCROW
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(min(limit.unwrap_or(DEFAULT_LIMIT), MAX_LIMIT) as usize)
+        .map(|val| {
+            let (_, liability) = val?;
+
+            // A token failing to answer must not hide the other assets from the monitoring
+            let (balance, error) = match liability.query_balance(deps, &env.contract.address) {
+                Ok(balance) => (balance, None),
+                Err(err) => (Uint128::zero(), Some(err.to_string())),
+            };
+
+            Ok(AssetSolvency {
+                balance,
+                liability,
+                error,
+            })
+        })
+        .collect::<StdResult<Vec<AssetSolvency>>>()?;
+
+    Ok(SolvencyResponse {
+        solvent: assets
+            .iter()
+            .all(|val| val.balance >= val.liability.get_amount()),
+        assets,
+    })
+}
+
+pub fn qy_nonce_used(deps: Deps, maker: String, nonce: u64) -> StdResult<bool> {
+    Ok(NONCES.has(deps.storage, (&maker.into_addr(deps.api)?, nonce)))
+}
+
+pub fn qy_ring_trade(deps: Deps, id: u64) -> StdResult<RingTrade> {
+    RING_TRADES.load(deps.storage, id)
+}
+
+pub fn qy_config(deps: Deps) -> StdResult<ConfigResponse> {
+    Ok(CONFIG.load(deps.storage)?.into())
+}
+
+pub fn qy_contract_info(deps: Deps) -> StdResult<ContractInfoResponse> {
+    let version = get_contract_version(deps.storage)?;
+
+    Ok(ContractInfoResponse {
+        contract: version.contract,
+        version: version.version,
+        max_items_per_side: CONFIG.load(deps.storage)?.max_items_per_side,
+        default_query_limit: DEFAULT_LIMIT,
+        max_query_limit: MAX_LIMIT,
+    })
+}
+
+pub fn qy_stats(deps: Deps) -> StdResult<StatsResponse> {
+    Ok(StatsResponse {
+        positions: STATUS_COUNTS
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|val| val.map(|(status, count)| StatusCount { status, count }))
+            .collect::<StdResult<_>>()?,
+        volume: asset_amounts(deps, VOLUMES)?,
+        fees: asset_amounts(deps, FEES)?,
+        traders: TRADERS_COUNT.may_load(deps.storage)?.unwrap_or_default(),
+    })
+}
+
+fn asset_amounts(deps: Deps, map: Map<String, Uint128>) -> StdResult<Vec<AssetAmount>> {
+    map.range(deps.storage, None, None, Order::Ascending)
+        .map(|val| val.map(|(asset, amount)| AssetAmount { asset, amount }))
+        .collect()
+}
+
+pub fn qy_daily_stats(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<DailyStats>> {
+    DAILY_STATS
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(min(limit.unwrap_or(DEFAULT_LIMIT), MAX_LIMIT) as usize)
+        .map(|val| val.map(|(_, val)| val))
+        .collect()
+}
+
+pub fn qy_vesting_timeline(deps: Deps, id: u64) -> StdResult<VestingTimelineResponse> {
+    let position = positions().load(deps.storage, id)?;
+
+    // The vesting start is known only while the position is vesting
+    let Ok(start) = position.status.get_vesting_start() else {
+        return Ok(VestingTimelineResponse {
+            offer: vec![],
+            ask: vec![],
+        });
+    };
+
+    let timeline = |items: &Vec<OtcItem>| {
+        items
+            .iter()
+            .map(|item| item.vesting_timeline(start, position.milestones.as_ref()))
+            .collect()
+    };
+
+    Ok(VestingTimelineResponse {
+        offer: timeline(&position.offer),
+        ask: timeline(&position.ask),
+    })
+}
+
+/// Run the `ClaimOtc` vesting math on a copy of the items, without saving it
+pub fn qy_claimable(
+    deps: Deps,
+    mut env: Env,
+    id: u64,
+    address: String,
+    at_time: Option<u64>,
+) -> StdResult<Vec<ClaimableItem>> {
+    let position = positions().load(deps.storage, id)?;
+    let address = address.into_addr(deps.api)?;
+
+    let items = if address == position.owner {
+        position.ask
+    } else if Some(&address) == position.executor.as_ref() {
+        position.offer
+    } else {
+        return Err(StdError::generic_err(
+            "Address is not a party of the position",
+        ));
+    };
+
+    if let Some(at_time) = at_time {
+        env.block.time = Timestamp::from_seconds(at_time);
+    }
+
+    items
+        .into_iter()
+        .filter(|val| val.vesting_info.is_some())
+        .map(|mut item| {
+            let (claimable, remaining) = match position.status {
+                // Nothing vests while pending
+                OtcPositionStatus::Pending => (Uint128::zero(), item.item_info.get_amount()),
+                OtcPositionStatus::Vesting(..) => {
+                    let claimable = item.sendable_amount_and_update_claimed_amount(
+                        &env,
+                        &position.status,
+                        position.milestones.as_ref(),
+                    )?;
+
+                    let claimed = item
+                        .vesting_info
+                        .as_ref()
+                        .map(|val| val.claimed)
+                        .unwrap_or_default();
+
+                    (claimable, item.item_info.get_amount() - claimed)
+                }
+                // Nothing is left to claim once the position is closed
+                OtcPositionStatus::Executed(..)
+                | OtcPositionStatus::Unwound(..)
+                | OtcPositionStatus::Arbitrated(..) => (Uint128::zero(), Uint128::zero()),
+            };
+
+            Ok(ClaimableItem {
+                item_info: item.item_info,
+                claimable,
+                remaining,
+            })
+        })
+        .collect()
 }
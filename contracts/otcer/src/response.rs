@@ -10,4 +10,34 @@ pub enum ContractError {
 
     #[error("Unauthorized")]
     Unauthorized {},
+
+    #[error("{side} can't be empty")]
+    EmptyItems { side: String },
+
+    #[error("{side} has more than {max} items")]
+    TooManyItems { side: String, max: u32 },
+
+    #[error("Amount can't be zero: {asset}")]
+    ZeroAmount { asset: String },
+
+    #[error("Asset listed more than once: {asset}")]
+    DuplicateAsset { asset: String },
+
+    #[error("Executor can't be the owner of the position")]
+    ExecutorIsOwner {},
+
+    #[error("Secret doesn't match the hash lock")]
+    InvalidSecret {},
+
+    #[error("Execution must be committed in a previous block")]
+    MissingCommitment {},
+
+    #[error("Invalid signature")]
+    InvalidSignature {},
+
+    #[error("Nonce already used or cancelled: {nonce}")]
+    NonceUsed { nonce: u64 },
+
+    #[error("Order expired")]
+    OrderExpired {},
 }
@@ -1,8 +1,65 @@
-use cw_storage_plus::{index_list, IndexedMap, Item, MultiIndex};
-use otcer_pkg::otcer::definitions::{Config, OtcPosition};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Empty, Order, StdError, StdResult, Storage, Uint128};
+use cw_storage_plus::{
+    index_list, Bound, Index, IndexedMap, Item, KeyDeserialize, Map, MultiIndex, Prefixer,
+    PrimaryKey,
+};
+use otcer_pkg::otcer::definitions::{
+    Config, DailyStats, ExecutionCommitment, OtcBid, OtcItemInfo, OtcPosition, RingTrade,
+};
 
 pub const CONFIG: Item<Config> = Item::new("config");
 
+/// Layout of `CONFIG` before the weighted fee collectors, converted by `migrate`
+pub const LEGACY_CONFIG: Item<LegacyConfig> = Item::new("config");
+
+#[cw_serde]
+pub struct LegacyConfig {
+    pub owner: Addr,
+    pub counter_otc: u64,
+    pub fee: Vec<OtcItemInfo>,
+    pub fee_collector: Addr,
+}
+
+/// Open bids, keyed by `(position_id, bid_id)`
+pub const BIDS: Map<(u64, u64), OtcBid> = Map::new("bids");
+
+/// Contracts receiving `OtcHookMsg` for every position
+pub const HOOKS: Map<&Addr, Empty> = Map::new("hooks");
+
+/// Fees accumulated by each referrer, keyed by `(referrer, asset_key)`
+pub const REFERRAL_TOTALS: Map<(&Addr, String), OtcItemInfo> = Map::new("referral_totals");
+
+/// Assets held by the contract on behalf of the positions and the bids, keyed by `asset_key`
+pub const ESCROW: Map<String, OtcItemInfo> = Map::new("escrow");
+
+/// Commitments to execute hash locked positions, keyed by `(position_id, executor)`
+pub const COMMITMENTS: Map<(u64, &Addr), ExecutionCommitment> = Map::new("commitments");
+
+/// Nonces of the signed orders used or cancelled, keyed by `(maker, nonce)`
+pub const NONCES: Map<(&Addr, u64), Empty> = Map::new("nonces");
+
+/// Pending unwind proposals, keyed by `position_id`, with the proposer as value
+pub const UNWIND_PROPOSALS: Map<u64, Addr> = Map::new("unwind_proposals");
+
+pub const RING_TRADES: Map<u64, RingTrade> = Map::new("ring_trades");
+
+/// Number of stored positions, keyed by status. Maintained by `StatusCounter`
+pub const STATUS_COUNTS: Map<String, u64> = Map::new("stats_status");
+
+/// Amounts traded, keyed by `asset_class`
+pub const VOLUMES: Map<String, Uint128> = Map::new("stats_volume");
+
+/// Fees collected, keyed by `asset_class`
+pub const FEES: Map<String, Uint128> = Map::new("stats_fees");
+
+/// Addresses that executed a trade, with their number in `TRADERS_COUNT`
+pub const TRADERS: Map<&Addr, Empty> = Map::new("stats_traders");
+
+pub const TRADERS_COUNT: Item<u64> = Item::new("stats_traders_count");
+
+pub const DAILY_STATS: Map<u64, DailyStats> = Map::new("daily_stats");
+
 pub type PositionMap<'a> = IndexedMap<'a, u64, OtcPosition, OtcPositionIndexer<'a>>;
 
 #[index_list(OtcPosition)]
@@ -14,6 +71,174 @@ pub struct OtcPositionIndexer<'a> {
     pub owner_status: MultiIndex<'a, (String, String), OtcPosition, u64>,
     pub executor_status: MultiIndex<'a, (String, String), OtcPosition, u64>,
     pub owner_executor_status: MultiIndex<'a, (String, String, String), OtcPosition, u64>,
+    pub offer: AssetIndex<'a, String>,
+    pub offer_status: AssetIndex<'a, (String, String)>,
+    pub ask: AssetIndex<'a, String>,
+    pub ask_status: AssetIndex<'a, (String, String)>,
+    pub pair: AssetIndex<'a, (String, String)>,
+    pub pair_status: AssetIndex<'a, (String, String, String)>,
+    pub status_count: StatusCounter,
+}
+
+/// Keep `STATUS_COUNTS` in sync with the saved and removed positions
+pub struct StatusCounter;
+
+impl Index<OtcPosition> for StatusCounter {
+    fn save(&self, store: &mut dyn Storage, _pk: &[u8], data: &OtcPosition) -> StdResult<()> {
+        STATUS_COUNTS.update(
+            store,
+            data.status.as_string_ref(),
+            |count| -> StdResult<_> { Ok(count.unwrap_or_default() + 1) },
+        )?;
+
+        Ok(())
+    }
+
+    fn remove(&self, store: &mut dyn Storage, _pk: &[u8], old_data: &OtcPosition) -> StdResult<()> {
+        // Positions stored before the counter was introduced are counted by `migrate`
+        STATUS_COUNTS.update(
+            store,
+            old_data.status.as_string_ref(),
+            |count| -> StdResult<_> {
+                count
+                    .unwrap_or_default()
+                    .checked_sub(1)
+                    .ok_or(StdError::generic_err("Position not counted"))
+            },
+        )?;
+
+        Ok(())
+    }
+}
+
+/// Index of the positions by the `asset_class` of their items.
+///
+/// Unlike `MultiIndex`, a position is indexed under every key returned by `index`
+pub struct AssetIndex<'a, IK> {
+    index: fn(&OtcPosition) -> Vec<IK>,
+    idx_map: Map<'a, (IK, u64), Empty>,
+}
+
+impl<'a, IK> AssetIndex<'a, IK>
+where
+    IK: PrimaryKey<'a> + Prefixer<'a> + KeyDeserialize + PartialEq,
+{
+    pub const fn new(index: fn(&OtcPosition) -> Vec<IK>, idx_namespace: &'a str) -> Self {
+        AssetIndex {
+            index,
+            idx_map: Map::new(idx_namespace),
+        }
+    }
+
+    /// Ids of the positions indexed under `key`
+    pub fn ids(
+        &self,
+        storage: &dyn Storage,
+        key: IK,
+        order: Order,
+        start_after: Option<u64>,
+        limit: usize,
+    ) -> StdResult<Vec<u64>> {
+        let (min, max) = match order {
+            Order::Ascending => (start_after.map(Bound::exclusive), None),
+            Order::Descending => (None, start_after.map(Bound::exclusive)),
+        };
+
+        self.idx_map
+            .prefix(key)
+            .keys(storage, min, max, order)
+            .take(limit)
+            .collect()
+    }
+
+    fn keys(&self, data: &OtcPosition) -> Vec<IK> {
+        let mut keys: Vec<IK> = vec![];
+
+        for key in (self.index)(data) {
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+
+        keys
+    }
+}
+
+impl<'a, IK> Index<OtcPosition> for AssetIndex<'a, IK>
+where
+    IK: PrimaryKey<'a> + Prefixer<'a> + KeyDeserialize + PartialEq,
+{
+    fn save(&self, store: &mut dyn Storage, pk: &[u8], data: &OtcPosition) -> StdResult<()> {
+        let id = u64::from_vec(pk.to_vec())?;
+
+        for key in self.keys(data) {
+            self.idx_map.save(store, (key, id), &Empty {})?;
+        }
+
+        Ok(())
+    }
+
+    fn remove(&self, store: &mut dyn Storage, pk: &[u8], old_data: &OtcPosition) -> StdResult<()> {
+        let id = u64::from_vec(pk.to_vec())?;
+
+        for key in self.keys(old_data) {
+            self.idx_map.remove(store, (key, id));
+        }
+
+        Ok(())
+    }
+}
+
+fn offer_classes(position: &OtcPosition) -> Vec<String> {
+    position
+        .offer
+        .iter()
+        .map(|item| item.item_info.asset_class())
+        .collect()
+}
+
+fn ask_classes(position: &OtcPosition) -> Vec<String> {
+    position
+        .ask
+        .iter()
+        .map(|item| item.item_info.asset_class())
+        .collect()
+}
+
+fn pair_classes(position: &OtcPosition) -> Vec<(String, String)> {
+    let ask = ask_classes(position);
+
+    offer_classes(position)
+        .into_iter()
+        .flat_map(|offer| ask.iter().map(move |ask| (offer.clone(), ask.clone())))
+        .collect()
+}
+
+/// Write the asset indexes and the status counts of the positions stored before them
+pub fn index_legacy_positions(storage: &mut dyn Storage) -> StdResult<()> {
+    let map = positions();
+
+    let stored_positions = map
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(u64, OtcPosition)>>>()?;
+
+    let indexes: [&dyn Index<OtcPosition>; 7] = [
+        &map.idx.offer,
+        &map.idx.offer_status,
+        &map.idx.ask,
+        &map.idx.ask_status,
+        &map.idx.pair,
+        &map.idx.pair_status,
+        &map.idx.status_count,
+    ];
+
+    for (id, position) in stored_positions {
+        for index in indexes {
+            index.save(storage, &id.to_be_bytes(), &position)?;
+        }
+    }
+
+    Ok(())
 }
 
 pub fn positions<'a>() -> PositionMap<'a> {
@@ -83,6 +308,37 @@ pub fn positions<'a>() -> PositionMap<'a> {
             "active_position",
             "active_position_owner_executor_status",
         ),
+        offer: AssetIndex::new(offer_classes, "active_position_offer"),
+        offer_status: AssetIndex::new(
+            |val| {
+                offer_classes(val)
+                    .into_iter()
+                    .map(|class| (class, val.status.as_string_ref()))
+                    .collect()
+            },
+            "active_position_offer_status",
+        ),
+        ask: AssetIndex::new(ask_classes, "active_position_ask"),
+        ask_status: AssetIndex::new(
+            |val| {
+                ask_classes(val)
+                    .into_iter()
+                    .map(|class| (class, val.status.as_string_ref()))
+                    .collect()
+            },
+            "active_position_ask_status",
+        ),
+        pair: AssetIndex::new(pair_classes, "active_position_pair"),
+        pair_status: AssetIndex::new(
+            |val| {
+                pair_classes(val)
+                    .into_iter()
+                    .map(|(offer, ask)| (offer, ask, val.status.as_string_ref()))
+                    .collect()
+            },
+            "active_position_pair_status",
+        ),
+        status_count: StatusCounter,
     };
 
     IndexedMap::new("active_position", indexer)
@@ -1,22 +1,102 @@
 pub mod msgs {
     use cosmwasm_schema::{cw_serde, QueryResponses};
-    use cosmwasm_std::Order;
+    use cosmwasm_std::{Addr, Binary, Decimal, HexBinary, Order, Uint128};
 
-    use super::definitions::{OtcItemInfo, OtcPosition};
+    use super::definitions::{
+        AssetAmount, Config, DailyStats, DisputeResolution, ExcessFundsPolicy, FeeCollector,
+        HookPolicy, ItemPricing, ListingBond, OtcBid, OtcItemInfo, OtcPosition, RingTrade, Tranche,
+        VestingEvent,
+    };
 
     #[cw_serde]
     pub struct InstantiateMsg {
         pub owner: String,
         pub fee: Vec<OtcItemInfo>,
-        pub fee_collector: String,
+        /// Recipients of the fees. Weights must sum to 1
+        pub fee_collectors: Vec<FeeCollectorRegistration>,
+        /// Share of the winning bid of an auction sent to the `fee_collectors`
+        pub auction_fee: Decimal,
+        /// Contract implementing `OracleQueryMsg`, used by `ItemPricing::Oracle`
+        pub price_oracle: Option<String>,
+        pub hook_policy: HookPolicy,
+        /// Share of the fee sent to the referrer of `CreateOtc` / `ExecuteOtc`
+        pub referral_share: Decimal,
+        pub excess_funds_policy: ExcessFundsPolicy,
+        /// Max number of items in the `offer` and in the `ask` of a position, or in a bid
+        pub max_items_per_side: u32,
+        /// Refundable bond required to create a position
+        pub listing_bond: Option<ListingBond>,
     }
 
     #[cw_serde]
     pub enum ExecuteMsg {
         CreateOtc(CreateOtcMsg),
         ExecuteOtc(ExecuteOtcMsg),
+        ExecuteMany(ExecuteManyMsg),
+        CreateRingTrade(CreateRingTradeMsg),
+        DepositRingLeg(DepositRingLegMsg),
+        RefundRingTrade(RefundRingTradeMsg),
+        CommitExecution(CommitExecutionMsg),
+        ExecuteSignedOrder(ExecuteSignedOrderMsg),
+        CancelNonces(CancelNoncesMsg),
         ClaimOtc(ClaimOtcMsg),
         CancelOtc(CancelOtcMsg),
+        SlashBond(SlashBondMsg),
+        ProposeUnwind(ProposeUnwindMsg),
+        AcceptUnwind(AcceptUnwindMsg),
+        ApproveMilestone(ApproveMilestoneMsg),
+        RaiseDispute(RaiseDisputeMsg),
+        ResolveDispute(ResolveDisputeMsg),
+        BidAuction(BidAuctionMsg),
+        SettleAuction(SettleAuctionMsg),
+        PlaceBid(PlaceBidMsg),
+        WithdrawBid(WithdrawBidMsg),
+        AcceptBid(AcceptBidMsg),
+        UpdateConfig(UpdateConfigMsg),
+        AddHook(AddHookMsg),
+        RemoveHook(RemoveHookMsg),
+        RecoverStuckFunds(RecoverStuckFundsMsg),
+    }
+
+    /// Owner only. `None` fields are left unchanged
+    #[cw_serde]
+    pub struct UpdateConfigMsg {
+        pub owner: Option<String>,
+        pub fee: Option<Vec<OtcItemInfo>>,
+        pub fee_collectors: Option<Vec<FeeCollectorRegistration>>,
+        pub auction_fee: Option<Decimal>,
+        pub price_oracle: Option<String>,
+        pub hook_policy: Option<HookPolicy>,
+        pub referral_share: Option<Decimal>,
+        pub excess_funds_policy: Option<ExcessFundsPolicy>,
+        pub max_items_per_side: Option<u32>,
+        /// A bond with a zero amount removes the listing bond
+        pub listing_bond: Option<ListingBond>,
+    }
+
+    /// Owner only. Send to `recipient` the amount of `asset` held by the contract
+    /// and not escrowed by any position or bid. Rejected if sent with funds
+    #[cw_serde]
+    pub struct RecoverStuckFundsMsg {
+        pub asset: OtcItemInfo,
+        pub recipient: String,
+    }
+
+    #[cw_serde]
+    pub struct FeeCollectorRegistration {
+        pub address: String,
+        pub weight: Decimal,
+    }
+
+    /// Owner only. Register a contract receiving `OtcHookMsg` for every position
+    #[cw_serde]
+    pub struct AddHookMsg {
+        pub contract: String,
+    }
+
+    #[cw_serde]
+    pub struct RemoveHookMsg {
+        pub contract: String,
     }
 
     #[cw_serde]
@@ -24,11 +104,131 @@ pub mod msgs {
         pub executor: Option<String>,
         pub offer: Vec<OtcItemRegistration>,
         pub ask: Vec<OtcItemRegistration>,
+        /// If provided, the position is sold through an english auction and `ask` must be empty
+        pub auction: Option<AuctionRegistration>,
+        /// Contract receiving `OtcHookMsg` for the actions on this position. Its failures are ignored
+        pub hook: Option<String>,
+        /// Receives `Config.referral_share` of the fee
+        pub referrer: Option<String>,
+        /// SHA-256 of a secret. The executor must commit with `CommitExecution` and reveal the secret
+        /// with `ExecuteOtc` in a later block
+        pub hash_lock: Option<HexBinary>,
+        /// Neutral party resolving the disputes raised with `RaiseDispute`
+        pub arbiter: Option<ArbiterRegistration>,
+        /// Milestones referenced by the `tranches` of the vesting items
+        pub milestones: Option<MilestonePlanRegistration>,
+    }
+
+    #[cw_serde]
+    pub struct MilestonePlanRegistration {
+        /// If not provided, milestones are completed by the approval of both the owner and the executor
+        pub approver: Option<String>,
+        /// Names of the milestones, referenced by index
+        pub milestones: Vec<String>,
+    }
+
+    #[cw_serde]
+    pub struct ArbiterRegistration {
+        pub address: String,
+        /// Share of the amounts distributed by `ResolveDispute` paid to the arbiter. Must be < 1
+        pub fee: Decimal,
     }
 
     #[cw_serde]
     pub struct ExecuteOtcMsg {
         pub id: u64,
+        /// Receives `Config.referral_share` of the fee
+        pub referrer: Option<String>,
+        /// Preimage of the `hash_lock` of the position
+        pub secret: Option<Binary>,
+    }
+
+    /// Execute several positions at once: the funds for all the `ask`s and fees
+    /// are sent together and either every position is executed or none is.
+    /// Hash locked positions can't be executed this way.
+    #[cw_serde]
+    pub struct ExecuteManyMsg {
+        pub ids: Vec<u64>,
+        /// Receives `Config.referral_share` of every fee
+        pub referrer: Option<String>,
+    }
+
+    /// Create a trade between several participants, each one depositing the items of
+    /// its leg for the recipient of the leg (e.g. A gives to B, B to C, C to A).
+    /// The fee is paid by the creator
+    #[cw_serde]
+    pub struct CreateRingTradeMsg {
+        pub legs: Vec<RingLegRegistration>,
+        /// Deposits are accepted until `deadline`, after that the trade can only be refunded
+        pub deadline: u64,
+    }
+
+    #[cw_serde]
+    pub struct RingLegRegistration {
+        pub participant: String,
+        /// Must be the participant of another leg
+        pub recipient: String,
+        /// Vesting and pricing are not allowed
+        pub items: Vec<OtcItemRegistration>,
+    }
+
+    /// Deposit the items of the leg of the sender.
+    /// The last deposit settles all the legs
+    #[cw_serde]
+    pub struct DepositRingLegMsg {
+        pub id: u64,
+    }
+
+    /// Return the deposits of a ring trade not settled before its deadline
+    #[cw_serde]
+    pub struct RefundRingTradeMsg {
+        pub id: u64,
+    }
+
+    /// Commit to execute a hash locked position.
+    /// `commitment` is the SHA-256 of the secret concatenated with the address of the sender
+    #[cw_serde]
+    pub struct CommitExecutionMsg {
+        pub id: u64,
+        pub commitment: HexBinary,
+    }
+
+    /// Order signed off-chain by the `maker`. The offer is pulled from the maker allowances,
+    /// so it can contain only Cw20 and Cw721 items
+    #[cw_serde]
+    pub struct SignedOrder {
+        pub maker: String,
+        pub offer: Vec<OtcItemRegistration>,
+        pub ask: Vec<OtcItemRegistration>,
+        pub executor: Option<String>,
+        /// Unix timestamp in seconds after which the order can't be executed
+        pub expiry: u64,
+        pub nonce: u64,
+    }
+
+    /// Payload signed by the maker: the secp256k1 signature is done over the SHA-256 of
+    /// its JSON serialization
+    #[cw_serde]
+    pub struct SignedOrderPayload {
+        pub chain_id: String,
+        pub contract: String,
+        pub order: SignedOrder,
+    }
+
+    #[cw_serde]
+    pub struct ExecuteSignedOrderMsg {
+        pub order: SignedOrder,
+        /// 64 bytes `r || s` signature
+        pub signature: Binary,
+        /// 33 bytes compressed secp256k1 public key of the maker
+        pub pubkey: Binary,
+    }
+
+    /// Invalidate nonces of the sender not used yet.
+    /// Nonces already used or cancelled are skipped
+    #[cw_serde]
+    pub struct CancelNoncesMsg {
+        pub nonces: Vec<u64>,
     }
 
     #[cw_serde]
@@ -41,6 +241,79 @@ pub mod msgs {
         pub id: u64,
     }
 
+    /// Close a position still pending after the expiry of its listing bond, sending the
+    /// bond to the `fee_collectors` and the offer back to the owner
+    #[cw_serde]
+    pub struct SlashBondMsg {
+        pub id: u64,
+    }
+
+    /// Propose to unwind a position in `Vesting`, by the owner or the executor
+    #[cw_serde]
+    pub struct ProposeUnwindMsg {
+        pub id: u64,
+    }
+
+    /// Accept the unwind proposed by the other side: the unclaimed amounts of the
+    /// vesting items are returned to their depositors and the position becomes `Unwound`
+    #[cw_serde]
+    pub struct AcceptUnwindMsg {
+        pub id: u64,
+    }
+
+    /// Complete a milestone of a position if sent by the approver, or approve it as one of the
+    /// parties when the position has no approver
+    #[cw_serde]
+    pub struct ApproveMilestoneMsg {
+        pub id: u64,
+        pub milestone: u32,
+    }
+
+    /// Open a dispute on a position with an arbiter, by the owner or the executor.
+    /// The position is frozen until the arbiter resolves the dispute
+    #[cw_serde]
+    pub struct RaiseDisputeMsg {
+        pub id: u64,
+    }
+
+    /// Resolve the dispute of a position, by its arbiter
+    #[cw_serde]
+    pub struct ResolveDisputeMsg {
+        pub id: u64,
+        pub resolution: DisputeResolution,
+    }
+
+    #[cw_serde]
+    pub struct BidAuctionMsg {
+        pub id: u64,
+        pub amount: Uint128,
+    }
+
+    #[cw_serde]
+    pub struct SettleAuctionMsg {
+        pub id: u64,
+    }
+
+    /// Escrow `items` as an alternative ask for the position `id`.
+    /// The bidder pays the `Config.fee` when placing the bid
+    #[cw_serde]
+    pub struct PlaceBidMsg {
+        pub id: u64,
+        pub items: Vec<OtcItemRegistration>,
+    }
+
+    #[cw_serde]
+    pub struct WithdrawBidMsg {
+        pub id: u64,
+        pub bid_id: u64,
+    }
+
+    #[cw_serde]
+    pub struct AcceptBidMsg {
+        pub id: u64,
+        pub bid_id: u64,
+    }
+
     #[cw_serde]
     #[derive(QueryResponses)]
     pub enum QueryMsg {
@@ -53,6 +326,163 @@ pub mod msgs {
             filters: Option<QueryPositionsFilter>,
             order: Option<QueryPositionsFilterOrder>,
         },
+        #[returns(Vec<OtcBid>)]
+        Bids {
+            id: u64,
+            limit: Option<u32>,
+            start_after: Option<u64>,
+        },
+        #[returns(Vec<Addr>)]
+        Hooks {},
+        /// Fees accumulated by a referrer, one item for each asset
+        #[returns(Vec<OtcItemInfo>)]
+        ReferralTotals { referrer: String },
+        /// Escrow ledger compared with the balances of the contract, paginated by `asset_key`
+        #[returns(SolvencyResponse)]
+        Solvency {
+            limit: Option<u32>,
+            start_after: Option<String>,
+        },
+        /// `true` if the nonce of the maker has been used or cancelled
+        #[returns(bool)]
+        NonceUsed { maker: String, nonce: u64 },
+        #[returns(RingTrade)]
+        RingTrade { id: u64 },
+        /// Simulate `ClaimOtc` for `address` at `at_time`, or at the current time.
+        /// One entry for each item with a vesting on the side claimable by `address`,
+        /// with nothing claimable while pending and once executed, unwound or arbitrated
+        #[returns(Vec<ClaimableItem>)]
+        Claimable {
+            id: u64,
+            address: String,
+            at_time: Option<u64>,
+        },
+        /// Unlock schedule of every item of a position in `Vesting` status,
+        /// empty for any other status
+        #[returns(VestingTimelineResponse)]
+        VestingTimeline { id: u64 },
+        #[returns(ConfigResponse)]
+        Config {},
+        /// cw2 name and version of the contract and its limits
+        #[returns(ContractInfoResponse)]
+        ContractInfo {},
+        #[returns(StatsResponse)]
+        Stats {},
+        /// Activity per day, from the oldest day
+        #[returns(Vec<DailyStats>)]
+        DailyStats {
+            start_after: Option<u64>,
+            limit: Option<u32>,
+        },
+    }
+
+    #[cw_serde]
+    pub struct StatsResponse {
+        /// Number of stored positions in each status
+        pub positions: Vec<StatusCount>,
+        /// Amounts traded by executed positions and settled ring trades
+        pub volume: Vec<AssetAmount>,
+        pub fees: Vec<AssetAmount>,
+        /// Addresses that executed a trade as either party
+        pub traders: u64,
+    }
+
+    #[cw_serde]
+    pub struct StatusCount {
+        pub status: String,
+        pub count: u64,
+    }
+
+    #[cw_serde]
+    pub struct ConfigResponse {
+        pub owner: Addr,
+        pub counter_otc: u64,
+        pub counter_bid: u64,
+        pub counter_ring: u64,
+        pub fee: Vec<OtcItemInfo>,
+        pub fee_collectors: Vec<FeeCollector>,
+        pub auction_fee: Decimal,
+        pub price_oracle: Option<Addr>,
+        pub hook_policy: HookPolicy,
+        pub referral_share: Decimal,
+        pub excess_funds_policy: ExcessFundsPolicy,
+        pub max_items_per_side: u32,
+        pub listing_bond: Option<ListingBond>,
+    }
+
+    impl From<Config> for ConfigResponse {
+        fn from(value: Config) -> Self {
+            ConfigResponse {
+                owner: value.owner,
+                counter_otc: value.counter_otc,
+                counter_bid: value.counter_bid,
+                counter_ring: value.counter_ring,
+                fee: value.fee,
+                fee_collectors: value.fee_collectors,
+                auction_fee: value.auction_fee,
+                price_oracle: value.price_oracle,
+                hook_policy: value.hook_policy,
+                referral_share: value.referral_share,
+                excess_funds_policy: value.excess_funds_policy,
+                max_items_per_side: value.max_items_per_side,
+                listing_bond: value.listing_bond,
+            }
+        }
+    }
+
+    #[cw_serde]
+    pub struct ContractInfoResponse {
+        pub contract: String,
+        pub version: String,
+        /// Max number of items in the `offer` and in the `ask` of a position, or in a bid
+        pub max_items_per_side: u32,
+        /// Page size of the paginated queries when `limit` is not provided
+        pub default_query_limit: u32,
+        /// Max page size of the paginated queries
+        pub max_query_limit: u32,
+    }
+
+    /// One entry for each item, in the order of the position
+    #[cw_serde]
+    pub struct VestingTimelineResponse {
+        pub offer: Vec<ItemVestingTimeline>,
+        pub ask: Vec<ItemVestingTimeline>,
+    }
+
+    #[cw_serde]
+    pub struct ItemVestingTimeline {
+        pub item_info: OtcItemInfo,
+        /// Cumulative unlocked amount over time, sorted by `time`
+        pub events: Vec<VestingEvent>,
+        /// Amount locked by tranches whose milestone is not completed yet
+        pub awaiting_milestones: Uint128,
+        pub claimed: Uint128,
+    }
+
+    #[cw_serde]
+    pub struct ClaimableItem {
+        pub item_info: OtcItemInfo,
+        /// Amount sent by `ClaimOtc`
+        pub claimable: Uint128,
+        /// Amount still locked after claiming `claimable`
+        pub remaining: Uint128,
+    }
+
+    #[cw_serde]
+    pub struct SolvencyResponse {
+        /// `false` if the balance of any returned asset is lower than its liability
+        pub solvent: bool,
+        pub assets: Vec<AssetSolvency>,
+    }
+
+    #[cw_serde]
+    pub struct AssetSolvency {
+        /// Amount owed to the positions and the bids
+        pub liability: OtcItemInfo,
+        /// Amount owned by the contract, zero if it can't be queried
+        pub balance: Uint128,
+        /// Error of the balance query, the asset is then reported as insolvent
+        pub error: Option<String>,
     }
 
     #[cw_serde]
@@ -62,12 +492,27 @@ pub mod msgs {
     pub struct VestingInfoRegistration {
         pub cliff: Option<u64>,
         pub vesting: Option<u64>,
+        /// Milestone-gated shares of the item, combined with the cliff and the vesting
+        pub tranches: Option<Vec<Tranche>>,
     }
 
     #[cw_serde]
     pub struct OtcItemRegistration {
         pub item_info: OtcItemInfo,
         pub vesting: Option<VestingInfoRegistration>,
+        /// Dynamic pricing of the amount. Allowed only for fungible `ask` items
+        pub pricing: Option<ItemPricing>,
+    }
+
+    #[cw_serde]
+    pub struct AuctionRegistration {
+        /// Asset accepted for the bids, with the minimum amount of the first bid.
+        /// Only `Token` and `Cw20` are allowed
+        pub reserve: OtcItemInfo,
+        pub min_increment: Uint128,
+        pub end_time: u64,
+        /// A bid placed less than `extension` seconds before `end_time` moves `end_time` to `now + extension`
+        pub extension: u64,
     }
 
     #[cw_serde]
@@ -75,6 +520,12 @@ pub mod msgs {
         pub owner: Option<String>,
         pub executor: Option<String>,
         pub status: Option<QueryPositionsFilterStatus>,
+        /// Native denom, cw20 contract or cw721 collection of an `offer` item,
+        /// composable only with `ask_asset` and `status`
+        pub offer_asset: Option<String>,
+        /// Native denom, cw20 contract or cw721 collection of an `ask` item,
+        /// composable only with `offer_asset` and `status`
+        pub ask_asset: Option<String>,
     }
 
     #[cw_serde]
@@ -82,6 +533,8 @@ pub mod msgs {
         Vesting,
         Pending,
         Executed,
+        Unwound,
+        Arbitrated,
     }
 
     impl QueryPositionsFilterStatus {
@@ -90,6 +543,8 @@ pub mod msgs {
                 QueryPositionsFilterStatus::Vesting => "vesting".to_string(),
                 QueryPositionsFilterStatus::Pending => "pending".to_string(),
                 QueryPositionsFilterStatus::Executed => "executed".to_string(),
+                QueryPositionsFilterStatus::Unwound => "unwound".to_string(),
+                QueryPositionsFilterStatus::Arbitrated => "arbitrated".to_string(),
             }
         }
     }
@@ -100,6 +555,39 @@ pub mod msgs {
         Descending,
     }
 
+    /// Message sent to the hook contracts after each action, wrapped in `OtcHookExecuteMsg`
+    #[cw_serde]
+    pub enum OtcHookMsg {
+        Created {
+            id: u64,
+            owner: Addr,
+        },
+        Executed {
+            id: u64,
+            owner: Addr,
+            executor: Addr,
+        },
+        Claimed {
+            id: u64,
+            claimer: Addr,
+        },
+        Cancelled {
+            id: u64,
+            owner: Addr,
+        },
+        Unwound {
+            id: u64,
+            owner: Addr,
+            executor: Addr,
+        },
+    }
+
+    /// Hook contracts must implement this variant in their `ExecuteMsg`
+    #[cw_serde]
+    pub enum OtcHookExecuteMsg {
+        OtcHook(OtcHookMsg),
+    }
+
     impl From<QueryPositionsFilterOrder> for Order {
         fn from(val: QueryPositionsFilterOrder) -> Self {
             match val {
@@ -111,48 +599,247 @@ pub mod msgs {
 }
 
 pub mod definitions {
-    use std::cmp::min;
+    use std::cmp::{max, min};
 
     use cosmwasm_schema::cw_serde;
     use cosmwasm_std::{
-        Addr, BankMsg, Coin, CosmosMsg, Decimal, Deps, Env, StdError, StdResult, Uint128, WasmMsg,
+        Addr, BankMsg, Coin, CosmosMsg, Decimal, Deps, Env, HexBinary, StdError, StdResult,
+        Uint128, WasmMsg,
     };
     use rhaki_cw_plus::{traits::IntoAddr, wasm::WasmMsgBuilder};
 
-    use super::msgs::{CreateOtcMsg, OtcItemRegistration, VestingInfoRegistration};
+    use super::{
+        msgs::{
+            ArbiterRegistration, AuctionRegistration, CreateOtcMsg, CreateRingTradeMsg,
+            FeeCollectorRegistration, InstantiateMsg, ItemVestingTimeline,
+            MilestonePlanRegistration, OtcItemRegistration, VestingInfoRegistration,
+        },
+        oracle::{OracleQueryMsg, PriceResponse},
+    };
+
+    const BPS_DENOMINATOR: u64 = 10_000;
 
     #[cw_serde]
     pub struct Config {
         pub owner: Addr,
         pub counter_otc: u64,
+        pub counter_bid: u64,
+        pub counter_ring: u64,
         pub fee: Vec<OtcItemInfo>,
-        pub fee_collector: Addr,
+        pub fee_collectors: Vec<FeeCollector>,
+        pub auction_fee: Decimal,
+        pub price_oracle: Option<Addr>,
+        pub hook_policy: HookPolicy,
+        pub referral_share: Decimal,
+        pub excess_funds_policy: ExcessFundsPolicy,
+        pub max_items_per_side: u32,
+        pub listing_bond: Option<ListingBond>,
     }
 
     impl Config {
-        pub fn new(
-            deps: Deps,
-            owner: Addr,
-            fee: Vec<OtcItemInfo>,
-            fee_collector: Addr,
-        ) -> StdResult<Config> {
-            for i in &fee {
+        pub fn new(deps: Deps, msg: InstantiateMsg) -> StdResult<Config> {
+            let config = Config {
+                owner: msg.owner.into_addr(deps.api)?,
+                counter_otc: 0,
+                counter_bid: 0,
+                counter_ring: 0,
+                fee: msg.fee,
+                fee_collectors: FeeCollector::from_registrations(deps, msg.fee_collectors)?,
+                auction_fee: msg.auction_fee,
+                price_oracle: msg
+                    .price_oracle
+                    .map(|val| val.into_addr(deps.api))
+                    .transpose()?,
+                hook_policy: msg.hook_policy,
+                referral_share: msg.referral_share,
+                excess_funds_policy: msg.excess_funds_policy,
+                max_items_per_side: msg.max_items_per_side,
+                listing_bond: msg.listing_bond,
+            };
+
+            config.validate(deps)?;
+
+            Ok(config)
+        }
+
+        pub fn validate(&self, deps: Deps) -> StdResult<()> {
+            for i in &self.fee {
+                if let OtcItemInfo::Cw721 { .. } = i {
+                    return Err(StdError::generic_err("Cw721 can't be used as fee"));
+                }
+
                 i.validate(deps)?;
             }
 
-            Ok(Config {
-                owner,
-                counter_otc: 0,
-                fee,
-                fee_collector,
-            })
+            FeeCollector::validate_weights(&self.fee_collectors)?;
+
+            if self.auction_fee >= Decimal::one() {
+                return Err(StdError::generic_err("Auction fee must be < 1"));
+            }
+
+            if self.referral_share > Decimal::one() {
+                return Err(StdError::generic_err("Referral share must be <= 1"));
+            }
+
+            if self.max_items_per_side == 0 {
+                return Err(StdError::generic_err("Max items per side must be > 0"));
+            }
+
+            if let Some(listing_bond) = &self.listing_bond {
+                listing_bond.validate(deps)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Bond taken at `CreateOtc`, returned when the position is executed or cancelled
+    /// before `duration` seconds and slashed after. Not required for auctions
+    #[cw_serde]
+    pub struct ListingBond {
+        /// Only `Token` and `Cw20` are allowed
+        pub asset: OtcItemInfo,
+        pub duration: u64,
+    }
+
+    impl ListingBond {
+        pub fn validate(&self, deps: Deps) -> StdResult<()> {
+            if let OtcItemInfo::Cw721 { .. } = self.asset {
+                return Err(StdError::generic_err("Cw721 can't be used as listing bond"));
+            }
+
+            if self.asset.get_amount().is_zero() {
+                return Err(StdError::generic_err("Listing bond amount must be > 0"));
+            }
+
+            if self.duration == 0 {
+                return Err(StdError::generic_err("Listing bond duration must be > 0"));
+            }
+
+            self.asset.validate(deps)
         }
     }
 
+    /// Listing bond paid by the owner of a position
+    #[cw_serde]
+    pub struct PositionBond {
+        pub asset: OtcItemInfo,
+        pub expiry: u64,
+        pub status: BondStatus,
+    }
+
+    #[cw_serde]
+    pub enum BondStatus {
+        Locked,
+        Returned,
+        Slashed,
+    }
+
+    #[cw_serde]
+    pub struct FeeCollector {
+        pub address: Addr,
+        pub weight: Decimal,
+    }
+
+    impl FeeCollector {
+        pub fn from_registrations(
+            deps: Deps,
+            registrations: Vec<FeeCollectorRegistration>,
+        ) -> StdResult<Vec<FeeCollector>> {
+            registrations
+                .into_iter()
+                .map(|val| {
+                    Ok(FeeCollector {
+                        address: val.address.into_addr(deps.api)?,
+                        weight: val.weight,
+                    })
+                })
+                .collect()
+        }
+
+        pub fn validate_weights(fee_collectors: &[FeeCollector]) -> StdResult<()> {
+            if fee_collectors.is_empty() {
+                return Err(StdError::generic_err(
+                    "At least one fee collector is required",
+                ));
+            }
+
+            let mut total = Decimal::zero();
+
+            for (index, fee_collector) in fee_collectors.iter().enumerate() {
+                if fee_collector.weight.is_zero() {
+                    return Err(StdError::generic_err("Fee collector weight must be > 0"));
+                }
+
+                if fee_collectors[..index]
+                    .iter()
+                    .any(|val| val.address == fee_collector.address)
+                {
+                    return Err(StdError::generic_err(format!(
+                        "Duplicated fee collector: {}",
+                        fee_collector.address
+                    )));
+                }
+
+                total += fee_collector.weight;
+            }
+
+            if total != Decimal::one() {
+                return Err(StdError::generic_err(format!(
+                    "Fee collector weights must sum to 1, got {total}"
+                )));
+            }
+
+            Ok(())
+        }
+
+        /// Split `amount` by weight. The rounding remainder goes to the first fee collector
+        pub fn split(fee_collectors: &[FeeCollector], amount: Uint128) -> Vec<(Addr, Uint128)> {
+            let mut shares: Vec<(Addr, Uint128)> = fee_collectors
+                .iter()
+                .map(|val| (val.address.clone(), amount * val.weight))
+                .collect();
+
+            let distributed: Uint128 = shares.iter().map(|(_, share)| share).sum();
+
+            if let Some((_, first)) = shares.first_mut() {
+                *first += amount - distributed;
+            }
+
+            shares
+        }
+    }
+
+    /// Behaviour when a hook registered with `AddHook` fails.
+    /// The failure of the `hook` of a position is always ignored
+    #[cw_serde]
+    pub enum HookPolicy {
+        /// The failure of a hook reverts the whole action
+        Revert,
+        /// The failure of a hook is ignored
+        Ignore,
+    }
+
+    /// Behaviour when the native funds sent exceed the amount required by the action
+    #[cw_serde]
+    pub enum ExcessFundsPolicy {
+        /// The excess is sent back to the sender
+        Refund,
+        /// The action fails
+        Reject,
+    }
+
     #[cw_serde]
     pub struct OtcItem {
         pub item_info: OtcItemInfo,
         pub vesting_info: Option<VestingInfo>,
+        pub pricing: Option<ItemPricing>,
+        /// Token info queried at creation, for display. `None` for native tokens
+        pub metadata: Option<TokenMetadata>,
+        /// Amount at the current price of an item with `pricing`, set only by the position
+        /// queries while the position is pending. `None` if the price can't be computed
+        #[serde(default)]
+        pub current_amount: Option<Uint128>,
     }
 
     impl OtcItem {
@@ -160,40 +847,68 @@ pub mod definitions {
             if let Some(vesting) = &self.vesting_info {
                 vesting.validate()?
             }
+
+            if let Some(pricing) = &self.pricing {
+                pricing.validate(&self.item_info)?
+            }
+
             self.item_info.validate(deps)
         }
 
+        /// Amount of the item at the current block time, computed from `pricing` if present
+        pub fn current_amount(
+            &self,
+            deps: Deps,
+            env: &Env,
+            price_oracle: Option<&Addr>,
+        ) -> StdResult<Uint128> {
+            match &self.pricing {
+                Some(ItemPricing::Dutch(dutch)) => {
+                    Ok(dutch.amount_at(self.item_info.get_amount(), env.block.time.seconds()))
+                }
+                Some(ItemPricing::Oracle(oracle_pricing)) => {
+                    let price_oracle =
+                        price_oracle.ok_or(StdError::generic_err("Price oracle not configured"))?;
+
+                    oracle_pricing.amount(deps, env, price_oracle, &self.item_info)
+                }
+                None => Ok(self.item_info.get_amount()),
+            }
+        }
+
+        /// Fix the amount of `item_info` to the current price
+        pub fn resolve_pricing(
+            &mut self,
+            deps: Deps,
+            env: &Env,
+            price_oracle: Option<&Addr>,
+        ) -> StdResult<()> {
+            if self.pricing.is_some() {
+                self.item_info =
+                    self.item_info
+                        .with_amount(self.current_amount(deps, env, price_oracle)?)?;
+            }
+
+            Ok(())
+        }
+
         pub fn sendable_amount_and_update_claimed_amount(
             &mut self,
             env: &Env,
             position_status: &OtcPositionStatus,
+            milestones: Option<&MilestonePlan>,
         ) -> StdResult<Uint128> {
             match &mut self.vesting_info {
                 Some(vesting_info) => {
-                    let max_amount = self.item_info.get_amount();
                     let vesting_start = position_status.get_vesting_start()?;
-                    let mut delta = env.block.time.seconds() - vesting_start;
+                    let elapsed = env.block.time.seconds().saturating_sub(vesting_start);
 
-                    let cliff_window = if let Some(cliff) = vesting_info.cliff {
-                        if cliff >= delta {
-                            return Ok(Uint128::zero());
-                        }
-                        cliff
-                    } else {
-                        0
-                    };
-
-                    delta -= cliff_window;
-
-                    let unchecked_claimable_amount = if let Some(vesting_window) =
-                        vesting_info.vesting
-                    {
-                        max_amount * Decimal::from_ratio(min(delta, vesting_window), vesting_window)
-                    } else {
-                        max_amount
-                    };
+                    let unchecked_claimable_amount = self.item_info.get_amount()
+                        * vesting_info.vested_share(elapsed)
+                        * vesting_info.unlocked_share(milestones);
 
-                    let claimabile_amount = unchecked_claimable_amount - vesting_info.claimed;
+                    let claimabile_amount =
+                        unchecked_claimable_amount.saturating_sub(vesting_info.claimed);
 
                     vesting_info.claimed += claimabile_amount;
 
@@ -202,6 +917,35 @@ pub mod definitions {
                 None => Ok(self.item_info.get_amount()),
             }
         }
+
+        /// Unlock schedule of the item for a position vesting since `start`.
+        /// Items without vesting are fully unlocked at `start`
+        pub fn vesting_timeline(
+            &self,
+            start: u64,
+            milestones: Option<&MilestonePlan>,
+        ) -> ItemVestingTimeline {
+            let amount = self.item_info.get_amount();
+
+            match &self.vesting_info {
+                Some(vesting_info) => ItemVestingTimeline {
+                    item_info: self.item_info.clone(),
+                    events: vesting_info.timeline(amount, start, milestones),
+                    awaiting_milestones: amount - amount * vesting_info.unlocked_share(milestones),
+                    claimed: vesting_info.claimed,
+                },
+                None => ItemVestingTimeline {
+                    item_info: self.item_info.clone(),
+                    events: vec![VestingEvent {
+                        time: start,
+                        unlocked: amount,
+                        kind: VestingEventKind::Start,
+                    }],
+                    awaiting_milestones: Uint128::zero(),
+                    claimed: amount,
+                },
+            }
+        }
     }
 
     impl From<OtcItemRegistration> for OtcItem {
@@ -209,25 +953,220 @@ pub mod definitions {
             OtcItem {
                 item_info: value.item_info,
                 vesting_info: value.vesting.map(|val| val.into()),
+                pricing: value.pricing,
+                metadata: None,
+                current_amount: None,
+            }
+        }
+    }
+
+    #[cw_serde]
+    pub enum ItemPricing {
+        /// The amount of the item decreases from the `item_info` amount to `floor`
+        Dutch(DutchPricing),
+        /// The amount of the item is computed from the `Config.price_oracle` price at execution.
+        /// The `item_info` amount is the minimum amount accepted
+        Oracle(OraclePricing),
+    }
+
+    impl ItemPricing {
+        pub fn validate(&self, item_info: &OtcItemInfo) -> StdResult<()> {
+            if let OtcItemInfo::Cw721 { .. } = item_info {
+                return Err(StdError::generic_err("Pricing can't be applied to a Cw721"));
+            }
+
+            match self {
+                ItemPricing::Dutch(dutch) => dutch.validate(item_info.get_amount()),
+                ItemPricing::Oracle(oracle_pricing) => oracle_pricing.validate(),
+            }
+        }
+    }
+
+    #[cw_serde]
+    pub struct DutchPricing {
+        pub floor: Uint128,
+        pub start_time: u64,
+        pub end_time: u64,
+        /// If provided, the amount decreases in `steps` equal steps instead of linearly
+        pub steps: Option<u64>,
+    }
+
+    impl DutchPricing {
+        pub fn validate(&self, start_amount: Uint128) -> StdResult<()> {
+            if self.floor.is_zero() {
+                return Err(StdError::generic_err("Dutch floor must be > 0"));
+            }
+
+            if self.floor >= start_amount {
+                return Err(StdError::generic_err("Dutch floor must be < start amount"));
+            }
+
+            if self.end_time <= self.start_time {
+                return Err(StdError::generic_err("Dutch end time must be > start time"));
+            }
+
+            if let Some(steps) = self.steps {
+                if steps == 0 {
+                    return Err(StdError::generic_err("Dutch steps must be > 0"));
+                }
+
+                if steps > self.end_time - self.start_time {
+                    return Err(StdError::generic_err(
+                        "Dutch steps must be <= end time - start time",
+                    ));
+                }
+            }
+
+            Ok(())
+        }
+
+        pub fn amount_at(&self, start_amount: Uint128, time: u64) -> Uint128 {
+            if time <= self.start_time {
+                return start_amount;
+            }
+
+            if time >= self.end_time {
+                return self.floor;
+            }
+
+            let elapsed = time - self.start_time;
+            let duration = self.end_time - self.start_time;
+
+            let decay_ratio = match self.steps {
+                Some(steps) => {
+                    Decimal::from_ratio((elapsed as u128 * steps as u128) / duration as u128, steps)
+                }
+                None => Decimal::from_ratio(elapsed, duration),
+            };
+
+            start_amount - (start_amount - self.floor) * decay_ratio
+        }
+    }
+
+    #[cw_serde]
+    pub struct OraclePricing {
+        /// Asset priced by the oracle (denom or cw20 address)
+        pub base: String,
+        pub base_amount: Uint128,
+        pub adjustment: Option<PriceAdjustment>,
+        /// Max age in seconds of the oracle price
+        pub max_staleness: u64,
+    }
+
+    impl OraclePricing {
+        pub fn validate(&self) -> StdResult<()> {
+            if self.base_amount.is_zero() {
+                return Err(StdError::generic_err("Oracle base amount must be > 0"));
+            }
+
+            match self.adjustment {
+                Some(PriceAdjustment::Premium(bps)) if bps > BPS_DENOMINATOR => {
+                    return Err(StdError::generic_err("Oracle premium must be <= 10000 bps"));
+                }
+                Some(PriceAdjustment::Discount(bps)) if bps >= BPS_DENOMINATOR => {
+                    return Err(StdError::generic_err("Oracle discount must be < 10000 bps"));
+                }
+                _ => {}
+            }
+
+            Ok(())
+        }
+
+        pub fn amount(
+            &self,
+            deps: Deps,
+            env: &Env,
+            price_oracle: &Addr,
+            item_info: &OtcItemInfo,
+        ) -> StdResult<Uint128> {
+            let quote = match item_info {
+                OtcItemInfo::Token { denom, .. } => denom.clone(),
+                OtcItemInfo::Cw20 { contract, .. } => contract.to_string(),
+                OtcItemInfo::Cw721 { .. } => {
+                    return Err(StdError::generic_err("Pricing can't be applied to a Cw721"))
+                }
+            };
+
+            let price: PriceResponse = deps.querier.query_wasm_smart(
+                price_oracle,
+                &OracleQueryMsg::Price {
+                    base: self.base.clone(),
+                    quote,
+                },
+            )?;
+
+            if env.block.time.seconds() > price.last_updated.saturating_add(self.max_staleness) {
+                return Err(StdError::generic_err(format!(
+                    "Oracle price is stale, last update: {}",
+                    price.last_updated
+                )));
             }
+
+            let factor = match self.adjustment {
+                Some(PriceAdjustment::Premium(bps)) => {
+                    Decimal::from_ratio(BPS_DENOMINATOR + bps, BPS_DENOMINATOR)
+                }
+                Some(PriceAdjustment::Discount(bps)) => {
+                    Decimal::from_ratio(BPS_DENOMINATOR - bps, BPS_DENOMINATOR)
+                }
+                None => Decimal::one(),
+            };
+
+            let amount = self.base_amount * price.price * factor;
+
+            Ok(max(amount, item_info.get_amount()))
         }
     }
 
+    /// Adjustment over the oracle price, in basis points
+    #[cw_serde]
+    pub enum PriceAdjustment {
+        Premium(u64),
+        Discount(u64),
+    }
+
     #[cw_serde]
     pub struct VestingInfo {
         pub cliff: Option<u64>,
         pub vesting: Option<u64>,
+        pub tranches: Option<Vec<Tranche>>,
         pub claimed: Uint128,
     }
 
     impl VestingInfo {
         pub fn validate(&self) -> StdResult<()> {
-            if self.cliff.is_none() && self.vesting.is_none() {
+            if self.cliff.is_none() && self.vesting.is_none() && self.tranches.is_none() {
                 return Err(StdError::generic_err(
-                    "VestingInfo must have a vesting or cliff info",
+                    "VestingInfo must have a vesting, cliff or tranches info",
                 ));
             }
 
+            if let Some(tranches) = &self.tranches {
+                if tranches.is_empty() {
+                    return Err(StdError::generic_err("Tranches can't be empty"));
+                }
+
+                for (index, tranche) in tranches.iter().enumerate() {
+                    if tranche.share.is_zero() {
+                        return Err(StdError::generic_err("Tranche share must be > 0"));
+                    }
+
+                    if tranches[..index]
+                        .iter()
+                        .any(|val| val.milestone == tranche.milestone)
+                    {
+                        return Err(StdError::generic_err(format!(
+                            "Milestone {} used by more than one tranche",
+                            tranche.milestone
+                        )));
+                    }
+                }
+
+                if tranches.iter().map(|val| val.share).sum::<Decimal>() != Decimal::one() {
+                    return Err(StdError::generic_err("Tranche shares must sum to 1"));
+                }
+            }
+
             if let Some(vesting) = self.vesting {
                 if vesting == 0 {
                     return Err(StdError::generic_err("Vesting must be > 0"));
@@ -242,6 +1181,188 @@ pub mod definitions {
 
             Ok(())
         }
+
+        /// Share of the item unlocked by the completed milestones, `1` without tranches
+        pub fn unlocked_share(&self, milestones: Option<&MilestonePlan>) -> Decimal {
+            self.unlocked_share_at(milestones, u64::MAX)
+        }
+
+        /// Share of the item unlocked by the milestones completed until `time`
+        fn unlocked_share_at(&self, milestones: Option<&MilestonePlan>, time: u64) -> Decimal {
+            match &self.tranches {
+                Some(tranches) => tranches
+                    .iter()
+                    .filter(|val| {
+                        milestones
+                            .and_then(|plan| plan.milestones.get(val.milestone as usize))
+                            .and_then(|milestone| milestone.completed)
+                            .is_some_and(|completed| completed <= time)
+                    })
+                    .map(|val| val.share)
+                    .sum(),
+                None => Decimal::one(),
+            }
+        }
+
+        /// Share of the item unlocked by the cliff and the vesting `elapsed` seconds after the start
+        pub fn vested_share(&self, elapsed: u64) -> Decimal {
+            let cliff = self.cliff.unwrap_or_default();
+
+            if self.cliff.is_some() && cliff >= elapsed {
+                return Decimal::zero();
+            }
+
+            match self.vesting {
+                Some(vesting) => Decimal::from_ratio(min(elapsed - cliff, vesting), vesting),
+                None => Decimal::one(),
+            }
+        }
+
+        /// Unlock events of `amount` for a vesting started at `start`.
+        ///
+        /// `Linear` events are reached linearly from the previous event, the others at once.
+        /// Tranches are included from the completion time of their milestone
+        pub fn timeline(
+            &self,
+            amount: Uint128,
+            start: u64,
+            milestones: Option<&MilestonePlan>,
+        ) -> Vec<VestingEvent> {
+            let unlocked_at = |time: u64, milestones_time: u64| {
+                amount
+                    * self.vested_share(time - start)
+                    * self.unlocked_share_at(milestones, milestones_time)
+            };
+
+            // Without vesting the cliff unlocks the amount the second after its end
+            let cliff_time = self
+                .cliff
+                .map(|cliff| start + cliff + u64::from(self.vesting.is_none()));
+
+            let mut times: Vec<u64> = self
+                .tranches
+                .iter()
+                .flatten()
+                .filter_map(|tranche| {
+                    milestones
+                        .and_then(|plan| plan.milestones.get(tranche.milestone as usize))
+                        .and_then(|milestone| milestone.completed)
+                })
+                .chain(cliff_time)
+                .chain(
+                    self.vesting
+                        .map(|vesting| start + self.cliff.unwrap_or_default() + vesting),
+                )
+                .filter(|time| *time > start)
+                .collect();
+
+            times.sort_unstable();
+            times.dedup();
+
+            let mut events = vec![VestingEvent {
+                time: start,
+                unlocked: unlocked_at(start, start),
+                kind: VestingEventKind::Start,
+            }];
+
+            for time in times {
+                let last = events.last().unwrap().unlocked;
+
+                let linear = unlocked_at(time, time - 1);
+
+                if self.vesting.is_some() && linear != last {
+                    events.push(VestingEvent {
+                        time,
+                        unlocked: linear,
+                        kind: VestingEventKind::Linear,
+                    });
+                }
+
+                let last = events.last().unwrap().unlocked;
+
+                let unlocked = unlocked_at(time, time);
+
+                if Some(time) == cliff_time {
+                    events.push(VestingEvent {
+                        time,
+                        unlocked,
+                        kind: VestingEventKind::Cliff,
+                    });
+                } else if unlocked != last {
+                    events.push(VestingEvent {
+                        time,
+                        unlocked,
+                        kind: VestingEventKind::Milestone,
+                    });
+                }
+            }
+
+            events
+        }
+    }
+
+    #[cw_serde]
+    pub struct VestingEvent {
+        pub time: u64,
+        /// Cumulative amount unlocked at `time`
+        pub unlocked: Uint128,
+        pub kind: VestingEventKind,
+    }
+
+    #[cw_serde]
+    pub enum VestingEventKind {
+        Start,
+        Cliff,
+        /// End of a linear segment of the vesting
+        Linear,
+        /// Completion of a milestone releasing a tranche
+        Milestone,
+    }
+
+    /// Share of an item released when the milestone at index `milestone` of the position is completed
+    #[cw_serde]
+    pub struct Tranche {
+        pub milestone: u32,
+        pub share: Decimal,
+    }
+
+    #[cw_serde]
+    pub struct MilestonePlan {
+        /// Marks the milestones as completed. If `None`, both the owner and the executor must approve
+        pub approver: Option<Addr>,
+        pub milestones: Vec<Milestone>,
+    }
+
+    impl MilestonePlan {
+        pub fn from_registration(
+            deps: Deps,
+            registration: MilestonePlanRegistration,
+        ) -> StdResult<MilestonePlan> {
+            Ok(MilestonePlan {
+                approver: registration
+                    .approver
+                    .map(|val| val.into_addr(deps.api))
+                    .transpose()?,
+                milestones: registration
+                    .milestones
+                    .into_iter()
+                    .map(|name| Milestone {
+                        name,
+                        approvals: vec![],
+                        completed: None,
+                    })
+                    .collect(),
+            })
+        }
+    }
+
+    #[cw_serde]
+    pub struct Milestone {
+        pub name: String,
+        /// Parties that approved the milestone, when there is no approver
+        pub approvals: Vec<Addr>,
+        /// Completion time
+        pub completed: Option<u64>,
     }
 
     impl From<VestingInfoRegistration> for VestingInfo {
@@ -249,36 +1370,160 @@ pub mod definitions {
             VestingInfo {
                 cliff: value.cliff,
                 vesting: value.vesting,
+                tranches: value.tranches,
                 claimed: Uint128::zero(),
             }
         }
-    }
+    }
+
+    #[cw_serde]
+    pub struct TokenMetadata {
+        pub name: String,
+        pub symbol: String,
+        /// `None` for Cw721
+        pub decimals: Option<u8>,
+    }
+
+    #[cw_serde]
+    pub enum OtcItemInfo {
+        Token { denom: String, amount: Uint128 },
+        Cw20 { contract: Addr, amount: Uint128 },
+        Cw721 { contract: Addr, token_id: String },
+    }
+
+    impl OtcItemInfo {
+        pub fn validate(&self, deps: Deps) -> StdResult<()> {
+            match self {
+                OtcItemInfo::Token { .. } => Ok(()),
+                OtcItemInfo::Cw20 { contract, .. } => {
+                    contract.to_string().into_addr(deps.api).map(|_| ())
+                }
+                OtcItemInfo::Cw721 { contract, .. } => {
+                    contract.to_string().into_addr(deps.api).map(|_| ())
+                }
+            }
+        }
+
+        pub fn get_amount(&self) -> Uint128 {
+            match self {
+                OtcItemInfo::Token { amount, .. } => *amount,
+                OtcItemInfo::Cw20 { amount, .. } => *amount,
+                OtcItemInfo::Cw721 { .. } => Uint128::one(),
+            }
+        }
+
+        /// Unique identifier of the asset, used as storage key
+        pub fn asset_key(&self) -> String {
+            match self {
+                OtcItemInfo::Token { denom, .. } => format!("native:{denom}"),
+                OtcItemInfo::Cw20 { contract, .. } => format!("cw20:{contract}"),
+                OtcItemInfo::Cw721 { contract, token_id } => format!("cw721:{contract}:{token_id}"),
+            }
+        }
+
+        /// Denom of a native token, contract of a cw20 or collection of a cw721,
+        /// regardless of the amount or the token id
+        pub fn asset_class(&self) -> String {
+            match self {
+                OtcItemInfo::Token { denom, .. } => denom.clone(),
+                OtcItemInfo::Cw20 { contract, .. } => contract.to_string(),
+                OtcItemInfo::Cw721 { contract, .. } => contract.to_string(),
+            }
+        }
 
-    #[cw_serde]
-    pub enum OtcItemInfo {
-        Token { denom: String, amount: Uint128 },
-        Cw20 { contract: Addr, amount: Uint128 },
-        Cw721 { contract: Addr, token_id: String },
-    }
+        /// Query the token contract to confirm that it's a real Cw20 / Cw721 and that the nft exists
+        pub fn query_metadata(&self, deps: Deps) -> StdResult<Option<TokenMetadata>> {
+            match self {
+                OtcItemInfo::Token { .. } => Ok(None),
+                OtcItemInfo::Cw20 { contract, .. } => {
+                    let response: cw20::TokenInfoResponse = deps
+                        .querier
+                        .query_wasm_smart(contract, &cw20::Cw20QueryMsg::TokenInfo {})
+                        .map_err(|_| {
+                            StdError::generic_err(format!("Not a Cw20 contract: {contract}"))
+                        })?;
 
-    impl OtcItemInfo {
-        pub fn validate(&self, deps: Deps) -> StdResult<()> {
+                    Ok(Some(TokenMetadata {
+                        name: response.name,
+                        symbol: response.symbol,
+                        decimals: Some(response.decimals),
+                    }))
+                }
+                OtcItemInfo::Cw721 { contract, token_id } => {
+                    let response: cw721::ContractInfoResponse = deps
+                        .querier
+                        .query_wasm_smart(contract, &cw721::Cw721QueryMsg::ContractInfo {})
+                        .map_err(|_| {
+                            StdError::generic_err(format!("Not a Cw721 contract: {contract}"))
+                        })?;
+
+                    deps.querier
+                        .query_wasm_smart::<cw721::OwnerOfResponse>(
+                            contract,
+                            &cw721::Cw721QueryMsg::OwnerOf {
+                                token_id: token_id.clone(),
+                                include_expired: None,
+                            },
+                        )
+                        .map_err(|_| {
+                            StdError::generic_err(format!("Nft not found: {contract}:{token_id}"))
+                        })?;
+
+                    Ok(Some(TokenMetadata {
+                        name: response.name,
+                        symbol: response.symbol,
+                        decimals: None,
+                    }))
+                }
+            }
+        }
+
+        /// Amount of the asset owned by `address`. For a Cw721 it's 1 if `address` is the owner of the nft, 0 otherwise
+        pub fn query_balance(&self, deps: Deps, address: &Addr) -> StdResult<Uint128> {
             match self {
-                OtcItemInfo::Token { .. } => Ok(()),
+                OtcItemInfo::Token { denom, .. } => {
+                    Ok(deps.querier.query_balance(address, denom)?.amount)
+                }
                 OtcItemInfo::Cw20 { contract, .. } => {
-                    contract.to_string().into_addr(deps.api).map(|_| ())
+                    let response: cw20::BalanceResponse = deps.querier.query_wasm_smart(
+                        contract,
+                        &cw20::Cw20QueryMsg::Balance {
+                            address: address.to_string(),
+                        },
+                    )?;
+                    Ok(response.balance)
                 }
-                OtcItemInfo::Cw721 { contract, .. } => {
-                    contract.to_string().into_addr(deps.api).map(|_| ())
+                OtcItemInfo::Cw721 { contract, token_id } => {
+                    let response: cw721::OwnerOfResponse = deps.querier.query_wasm_smart(
+                        contract,
+                        &cw721::Cw721QueryMsg::OwnerOf {
+                            token_id: token_id.clone(),
+                            include_expired: None,
+                        },
+                    )?;
+                    if response.owner == address.as_str() {
+                        Ok(Uint128::one())
+                    } else {
+                        Ok(Uint128::zero())
+                    }
                 }
             }
         }
 
-        pub fn get_amount(&self) -> Uint128 {
+        /// Return the same fungible asset with a different amount
+        pub fn with_amount(&self, amount: Uint128) -> StdResult<OtcItemInfo> {
             match self {
-                OtcItemInfo::Token { amount, .. } => *amount,
-                OtcItemInfo::Cw20 { amount, .. } => *amount,
-                OtcItemInfo::Cw721 { .. } => Uint128::one(),
+                OtcItemInfo::Token { denom, .. } => Ok(OtcItemInfo::Token {
+                    denom: denom.clone(),
+                    amount,
+                }),
+                OtcItemInfo::Cw20 { contract, .. } => Ok(OtcItemInfo::Cw20 {
+                    contract: contract.clone(),
+                    amount,
+                }),
+                OtcItemInfo::Cw721 { .. } => {
+                    Err(StdError::generic_err("Cw721 doesn't have an amount"))
+                }
             }
         }
 
@@ -338,6 +1583,13 @@ pub mod definitions {
         }
     }
 
+    /// Commitment of an executor to a hash locked position
+    #[cw_serde]
+    pub struct ExecutionCommitment {
+        pub commitment: HexBinary,
+        pub height: u64,
+    }
+
     #[cw_serde]
     pub struct OtcPosition {
         pub id: u64,
@@ -347,10 +1599,18 @@ pub mod definitions {
         pub ask: Vec<OtcItem>,
         pub creation_time: u64,
         pub status: OtcPositionStatus,
+        pub auction: Option<AuctionInfo>,
+        pub hook: Option<Addr>,
+        pub hash_lock: Option<HexBinary>,
+        pub arbiter: Option<Arbiter>,
+        /// Open dispute, set by `RaiseDispute` and cleared by `ResolveDispute`
+        pub dispute: Option<Dispute>,
+        pub milestones: Option<MilestonePlan>,
+        pub bond: Option<PositionBond>,
     }
 
     impl OtcPosition {
-        pub fn validate(&self, deps: Deps) -> StdResult<()> {
+        pub fn validate(&self, deps: Deps, env: &Env) -> StdResult<()> {
             if let Some(executor) = &self.executor {
                 executor.to_string().into_addr(deps.api)?;
             }
@@ -359,8 +1619,97 @@ pub mod definitions {
                 item.validate(deps)?;
             }
 
+            if self.offer.iter().any(|item| item.pricing.is_some()) {
+                return Err(StdError::generic_err(
+                    "Pricing is allowed only for ask items",
+                ));
+            }
+
+            if let Some(auction) = &self.auction {
+                if !self.ask.is_empty() {
+                    return Err(StdError::generic_err(
+                        "Ask must be empty for an auction position",
+                    ));
+                }
+
+                if self.executor.is_some() {
+                    return Err(StdError::generic_err(
+                        "Executor can't be set for an auction position",
+                    ));
+                }
+
+                if self.hash_lock.is_some() {
+                    return Err(StdError::generic_err(
+                        "Hash lock can't be set for an auction position",
+                    ));
+                }
+
+                if self.arbiter.is_some() {
+                    return Err(StdError::generic_err(
+                        "Arbiter can't be set for an auction position",
+                    ));
+                }
+
+                auction.validate(deps, env)?;
+            }
+
+            if let Some(hash_lock) = &self.hash_lock {
+                if hash_lock.len() != 32 {
+                    return Err(StdError::generic_err("Hash lock must be a SHA-256 hash"));
+                }
+            }
+
+            if let Some(arbiter) = &self.arbiter {
+                if arbiter.address == self.owner || Some(&arbiter.address) == self.executor.as_ref()
+                {
+                    return Err(StdError::generic_err(
+                        "Arbiter can't be a party of the position",
+                    ));
+                }
+
+                if arbiter.fee >= Decimal::one() {
+                    return Err(StdError::generic_err("Arbiter fee must be < 1"));
+                }
+            }
+
+            if let Some(plan) = &self.milestones {
+                if plan.milestones.is_empty() {
+                    return Err(StdError::generic_err("Milestones can't be empty"));
+                }
+
+                if let Some(approver) = &plan.approver {
+                    if *approver == self.owner || Some(approver) == self.executor.as_ref() {
+                        return Err(StdError::generic_err(
+                            "Milestone approver can't be a party of the position",
+                        ));
+                    }
+                }
+            }
+
+            let milestones_len = self
+                .milestones
+                .as_ref()
+                .map(|plan| plan.milestones.len())
+                .unwrap_or_default();
+
+            for item in self.offer.iter().chain(self.ask.iter()) {
+                for tranche in item
+                    .vesting_info
+                    .iter()
+                    .flat_map(|val| val.tranches.iter().flatten())
+                {
+                    if tranche.milestone as usize >= milestones_len {
+                        return Err(StdError::generic_err(format!(
+                            "Milestone {} not defined",
+                            tranche.milestone
+                        )));
+                    }
+                }
+            }
+
             Ok(())
         }
+
         pub fn from_create_otc_msg(
             deps: Deps,
             env: &Env,
@@ -379,9 +1728,56 @@ pub mod definitions {
                 ask: msg.ask.into_iter().map(|val| val.into()).collect(),
                 creation_time: env.block.time.seconds(),
                 status: OtcPositionStatus::Pending,
+                auction: msg.auction.map(|val| val.into()),
+                hook: msg.hook.map(|val| val.into_addr(deps.api)).transpose()?,
+                hash_lock: msg.hash_lock,
+                arbiter: msg
+                    .arbiter
+                    .map(|val| Arbiter::from_registration(deps, val))
+                    .transpose()?,
+                dispute: None,
+                milestones: msg
+                    .milestones
+                    .map(|val| MilestonePlan::from_registration(deps, val))
+                    .transpose()?,
+                bond: None,
             })
         }
 
+        /// Verify the token contracts of the items and the auction reserve, caching the metadata in the items
+        pub fn load_metadata(&mut self, deps: Deps) -> StdResult<()> {
+            for item in self.offer.iter_mut().chain(self.ask.iter_mut()) {
+                item.metadata = item.item_info.query_metadata(deps)?;
+            }
+
+            if let Some(auction) = &self.auction {
+                auction.reserve.query_metadata(deps)?;
+            }
+
+            Ok(())
+        }
+
+        /// `true` if the listing bond expired, the position can't be executed anymore
+        pub fn is_expired(&self, env: &Env) -> bool {
+            self.bond
+                .as_ref()
+                .is_some_and(|bond| bond.expiry <= env.block.time.seconds())
+        }
+
+        /// Fix the amounts of the `ask` items to the current price
+        pub fn resolve_ask_pricing(
+            &mut self,
+            deps: Deps,
+            env: &Env,
+            price_oracle: Option<&Addr>,
+        ) -> StdResult<()> {
+            for item in self.ask.iter_mut() {
+                item.resolve_pricing(deps, env, price_oracle)?;
+            }
+
+            Ok(())
+        }
+
         pub fn active(&mut self, env: &Env, executor: &Addr) -> StdResult<()> {
             if let Some(saved_executor) = &self.executor {
                 if saved_executor != executor {
@@ -391,6 +1787,10 @@ pub mod definitions {
                 self.executor = Some(executor.clone())
             };
 
+            if self.arbiter.as_ref().map(|val| &val.address) == Some(executor) {
+                return Err(StdError::generic_err("Arbiter can't execute the position"));
+            }
+
             match self.status {
                 OtcPositionStatus::Pending => {
                     self.status = OtcPositionStatus::Vesting(env.block.time.seconds())
@@ -407,7 +1807,7 @@ pub mod definitions {
                     .ask
                     .clone()
                     .into_iter()
-                    .chain(self.offer.clone().into_iter())
+                    .chain(self.offer.clone())
                     .collect();
 
                 for item in all_items {
@@ -427,11 +1827,378 @@ pub mod definitions {
         }
     }
 
+    #[cw_serde]
+    pub struct Arbiter {
+        pub address: Addr,
+        pub fee: Decimal,
+    }
+
+    impl Arbiter {
+        pub fn from_registration(
+            deps: Deps,
+            registration: ArbiterRegistration,
+        ) -> StdResult<Arbiter> {
+            Ok(Arbiter {
+                address: registration.address.into_addr(deps.api)?,
+                fee: registration.fee,
+            })
+        }
+    }
+
+    #[cw_serde]
+    pub struct Dispute {
+        pub raised_by: Addr,
+        pub time: u64,
+    }
+
+    /// Decision of the arbiter on the unclaimed amounts of a disputed position
+    #[cw_serde]
+    pub enum DisputeResolution {
+        /// Release the unclaimed amounts to the counterparties, ignoring the vesting
+        Settle,
+        /// Return the unclaimed amounts to the depositors
+        Refund,
+        /// Release `settle_share` of the unclaimed amounts to the counterparties and return
+        /// the rest to the depositors. Not allowed if a Cw721 is unclaimed
+        ProRata { settle_share: Decimal },
+    }
+
+    impl DisputeResolution {
+        /// Share of the unclaimed amounts released to the counterparties
+        pub fn settle_share(&self) -> StdResult<Decimal> {
+            match self {
+                DisputeResolution::Settle => Ok(Decimal::one()),
+                DisputeResolution::Refund => Ok(Decimal::zero()),
+                DisputeResolution::ProRata { settle_share } => {
+                    if *settle_share > Decimal::one() {
+                        return Err(StdError::generic_err("Settle share must be <= 1"));
+                    }
+
+                    Ok(*settle_share)
+                }
+            }
+        }
+
+        pub fn as_string(&self) -> String {
+            match self {
+                DisputeResolution::Settle => "settle".to_string(),
+                DisputeResolution::Refund => "refund".to_string(),
+                DisputeResolution::ProRata { settle_share } => format!("pro_rata_{settle_share}"),
+            }
+        }
+    }
+
+    #[cw_serde]
+    pub struct AuctionInfo {
+        pub reserve: OtcItemInfo,
+        pub min_increment: Uint128,
+        pub end_time: u64,
+        pub extension: u64,
+        pub highest_bid: Option<AuctionBid>,
+    }
+
+    impl AuctionInfo {
+        pub fn validate(&self, deps: Deps, env: &Env) -> StdResult<()> {
+            if let OtcItemInfo::Cw721 { .. } = self.reserve {
+                return Err(StdError::generic_err("Auction reserve can't be a Cw721"));
+            }
+
+            self.reserve.validate(deps)?;
+
+            if self.min_increment.is_zero() {
+                return Err(StdError::generic_err("Min increment must be > 0"));
+            }
+
+            if self.end_time <= env.block.time.seconds() {
+                return Err(StdError::generic_err(
+                    "Auction end time must be in the future",
+                ));
+            }
+
+            Ok(())
+        }
+
+        pub fn is_ended(&self, env: &Env) -> bool {
+            env.block.time.seconds() >= self.end_time
+        }
+
+        pub fn min_next_bid(&self) -> Uint128 {
+            match &self.highest_bid {
+                Some(bid) => bid.amount + self.min_increment,
+                None => self.reserve.get_amount(),
+            }
+        }
+
+        /// Register a new highest bid, extending `end_time` if the bid falls in the anti-sniping window.
+        ///
+        /// Return the outbid bid, if any
+        pub fn place_bid(
+            &mut self,
+            env: &Env,
+            bidder: Addr,
+            amount: Uint128,
+        ) -> StdResult<Option<AuctionBid>> {
+            if self.is_ended(env) {
+                return Err(StdError::generic_err("Auction is ended"));
+            }
+
+            let min_bid = self.min_next_bid();
+
+            if amount < min_bid {
+                return Err(StdError::generic_err(format!(
+                    "Bid too low: min: {min_bid}, received: {amount}"
+                )));
+            }
+
+            let now = env.block.time.seconds();
+
+            if self.end_time - now < self.extension {
+                self.end_time = now + self.extension
+            }
+
+            Ok(self.highest_bid.replace(AuctionBid {
+                bidder,
+                amount,
+                time: now,
+            }))
+        }
+    }
+
+    impl From<AuctionRegistration> for AuctionInfo {
+        fn from(value: AuctionRegistration) -> Self {
+            AuctionInfo {
+                reserve: value.reserve,
+                min_increment: value.min_increment,
+                end_time: value.end_time,
+                extension: value.extension,
+                highest_bid: None,
+            }
+        }
+    }
+
+    #[cw_serde]
+    pub struct AuctionBid {
+        pub bidder: Addr,
+        pub amount: Uint128,
+        pub time: u64,
+    }
+
+    #[cw_serde]
+    pub struct OtcBid {
+        pub id: u64,
+        pub position_id: u64,
+        pub bidder: Addr,
+        pub items: Vec<OtcItem>,
+        pub creation_time: u64,
+        /// Fee escrowed with the bid: sent to the `fee_collectors` if the bid is accepted,
+        /// returned to the bidder otherwise
+        #[serde(default)]
+        pub fee: Vec<OtcItemInfo>,
+    }
+
+    impl OtcBid {
+        /// Items and fee held by the contract for the bid
+        pub fn escrowed_items(&self) -> Vec<OtcItemInfo> {
+            self.items
+                .iter()
+                .map(|val| val.item_info.clone())
+                .chain(self.fee.clone())
+                .collect()
+        }
+
+        pub fn validate(&self, deps: Deps) -> StdResult<()> {
+            if self.items.is_empty() {
+                return Err(StdError::generic_err("Bid must have at least one item"));
+            }
+
+            for item in &self.items {
+                item.validate(deps)?;
+
+                if item.pricing.is_some() {
+                    return Err(StdError::generic_err(
+                        "Pricing is not allowed for bid items",
+                    ));
+                }
+
+                if item
+                    .vesting_info
+                    .as_ref()
+                    .is_some_and(|val| val.tranches.is_some())
+                {
+                    return Err(StdError::generic_err(
+                        "Tranches are not allowed for bid items",
+                    ));
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    #[cw_serde]
+    pub struct RingTrade {
+        pub id: u64,
+        pub creator: Addr,
+        pub legs: Vec<RingLeg>,
+        pub deadline: u64,
+        pub creation_time: u64,
+        pub status: RingTradeStatus,
+    }
+
+    impl RingTrade {
+        pub fn validate(&self, deps: Deps, env: &Env) -> StdResult<()> {
+            if self.legs.len() < 2 {
+                return Err(StdError::generic_err(
+                    "Ring trade must have at least two legs",
+                ));
+            }
+
+            if self.deadline <= env.block.time.seconds() {
+                return Err(StdError::generic_err("Deadline must be in the future"));
+            }
+
+            for (index, leg) in self.legs.iter().enumerate() {
+                if self.legs[..index]
+                    .iter()
+                    .any(|val| val.participant == leg.participant)
+                {
+                    return Err(StdError::generic_err(format!(
+                        "Participant listed more than once: {}",
+                        leg.participant
+                    )));
+                }
+
+                if leg.recipient == leg.participant
+                    || !self.legs.iter().any(|val| val.participant == leg.recipient)
+                {
+                    return Err(StdError::generic_err(format!(
+                        "Recipient of {} must be another participant",
+                        leg.participant
+                    )));
+                }
+
+                for item in &leg.items {
+                    item.validate(deps)?;
+
+                    if item.vesting_info.is_some() || item.pricing.is_some() {
+                        return Err(StdError::generic_err(
+                            "Vesting and pricing are not allowed for ring trade items",
+                        ));
+                    }
+                }
+            }
+
+            Ok(())
+        }
+
+        pub fn from_create_ring_trade_msg(
+            deps: Deps,
+            env: &Env,
+            msg: CreateRingTradeMsg,
+            id: u64,
+            creator: Addr,
+        ) -> StdResult<RingTrade> {
+            Ok(RingTrade {
+                id,
+                creator,
+                legs: msg
+                    .legs
+                    .into_iter()
+                    .map(|val| {
+                        Ok(RingLeg {
+                            participant: val.participant.into_addr(deps.api)?,
+                            recipient: val.recipient.into_addr(deps.api)?,
+                            items: val.items.into_iter().map(|val| val.into()).collect(),
+                            deposited: false,
+                        })
+                    })
+                    .collect::<StdResult<Vec<RingLeg>>>()?,
+                deadline: msg.deadline,
+                creation_time: env.block.time.seconds(),
+                status: RingTradeStatus::Pending,
+            })
+        }
+
+        /// Verify the token contracts of the items, caching the metadata in the items
+        pub fn load_metadata(&mut self, deps: Deps) -> StdResult<()> {
+            for item in self.legs.iter_mut().flat_map(|val| val.items.iter_mut()) {
+                item.metadata = item.item_info.query_metadata(deps)?;
+            }
+
+            Ok(())
+        }
+
+        pub fn is_fully_deposited(&self) -> bool {
+            self.legs.iter().all(|val| val.deposited)
+        }
+    }
+
+    #[cw_serde]
+    pub struct RingLeg {
+        pub participant: Addr,
+        pub recipient: Addr,
+        pub items: Vec<OtcItem>,
+        pub deposited: bool,
+    }
+
+    /// Activity of a day, keyed by `day` (block time / 86400)
+    #[cw_serde]
+    pub struct DailyStats {
+        pub day: u64,
+        pub created: u64,
+        /// Executed positions and settled ring trades
+        pub executed: u64,
+        pub volume: Vec<AssetAmount>,
+        pub fees: Vec<AssetAmount>,
+        /// Addresses trading for the first time
+        pub new_traders: u64,
+    }
+
+    impl DailyStats {
+        pub fn new(day: u64) -> DailyStats {
+            DailyStats {
+                day,
+                created: 0,
+                executed: 0,
+                volume: vec![],
+                fees: vec![],
+                new_traders: 0,
+            }
+        }
+    }
+
+    /// Amount aggregated by `asset_class`. For cw721 collections it's the number of tokens
+    #[cw_serde]
+    pub struct AssetAmount {
+        pub asset: String,
+        pub amount: Uint128,
+    }
+
+    impl AssetAmount {
+        pub fn add(list: &mut Vec<AssetAmount>, item_info: &OtcItemInfo, amount: Uint128) {
+            let asset = item_info.asset_class();
+
+            match list.iter_mut().find(|val| val.asset == asset) {
+                Some(total) => total.amount += amount,
+                None => list.push(AssetAmount { asset, amount }),
+            }
+        }
+    }
+
+    #[cw_serde]
+    pub enum RingTradeStatus {
+        Pending,
+        Settled(u64),
+        Refunded(u64),
+    }
+
     #[cw_serde]
     pub enum OtcPositionStatus {
         Pending,
         Vesting(u64),
         Executed(u64),
+        Unwound(u64),
+        Arbitrated(u64),
     }
 
     impl OtcPositionStatus {
@@ -451,7 +2218,32 @@ pub mod definitions {
                 OtcPositionStatus::Pending => "pending".to_string(),
                 OtcPositionStatus::Vesting(_) => "vesting".to_string(),
                 OtcPositionStatus::Executed(_) => "executed".to_string(),
+                OtcPositionStatus::Unwound(_) => "unwound".to_string(),
+                OtcPositionStatus::Arbitrated(_) => "arbitrated".to_string(),
             }
         }
     }
 }
+
+pub mod oracle {
+    //! Query interface that a price oracle must implement to be used with `ItemPricing::Oracle`
+
+    use cosmwasm_schema::{cw_serde, QueryResponses};
+    use cosmwasm_std::Decimal;
+
+    #[cw_serde]
+    #[derive(QueryResponses)]
+    pub enum OracleQueryMsg {
+        /// Price of one unit of `base` expressed in units of `quote`.
+        /// Assets are identified by denom for native tokens and by address for cw20
+        #[returns(PriceResponse)]
+        Price { base: String, quote: String },
+    }
+
+    #[cw_serde]
+    pub struct PriceResponse {
+        pub price: Decimal,
+        /// Timestamp in seconds of the last price update
+        pub last_updated: u64,
+    }
+}
@@ -0,0 +1,89 @@
+use cw_multi_test::Executor;
+use otcer_pkg::otcer::{
+    definitions::OtcItemInfo,
+    msgs::{
+        CancelOtcMsg, ExecuteMsg, OtcItemRegistration, QueryPositionsFilter,
+        QueryPositionsFilterStatus,
+    },
+};
+use rhaki_cw_plus::traits::IntoAddr;
+
+use crate::helper::{
+    create_token, increase_allowance, mint_token, qy_positions, run_create_otc, run_execute_otc,
+    startup, Def, TokenType,
+};
+
+#[test]
+#[rustfmt::skip]
+pub fn test_asset_filters() {
+    let mut def = Def::new();
+
+    let mut app = startup(&mut def);
+
+    let creator = "creator";
+    let executor = "executor";
+    let otc = def.addr_otc.clone().unwrap();
+
+    let fee = def.get_native_fee();
+
+    let token = create_token(&mut app, &mut def, "Token", TokenType::Cw20, vec![(creator, "150")]);
+    let collection = create_token(&mut app, &mut def, "Nft", TokenType::Cw721, vec![(creator, "1")]);
+
+    increase_allowance(&mut app, creator, otc.as_str(), &token, TokenType::Cw20, "150");
+    increase_allowance(&mut app, creator, otc.as_str(), &collection, TokenType::Cw721, "1");
+
+    mint_token(&mut app, &mut def, creator, ("luna", TokenType::Native), "10");
+    mint_token(&mut app, &mut def, creator, (&fee[0].denom, TokenType::Native), &(fee[0].amount.u128() * 3).to_string());
+    mint_token(&mut app, &mut def, executor, ("usdc", TokenType::Native), "50");
+    mint_token(&mut app, &mut def, executor, (&fee[0].denom, TokenType::Native), &fee[0].amount.to_string());
+
+    let item = |item_info: OtcItemInfo| OtcItemRegistration { item_info, vesting: None, pricing: None };
+
+    let cw20 = |amount: u128| item(OtcItemInfo::Cw20 { contract: token.clone(), amount: amount.into() });
+    let native = |denom: &str, amount: u128| item(OtcItemInfo::Token { denom: denom.to_string(), amount: amount.into() });
+
+    run_create_otc(&mut app, &mut def, creator, Some(executor), &[cw20(100)], &[native("usdc", 50)], fee.clone()).unwrap();
+    run_create_otc(&mut app, &mut def, creator, None, &[item(OtcItemInfo::Cw721 { contract: collection.clone(), token_id: "1".to_string() })], &[native("usdc", 70)], fee.clone()).unwrap();
+
+    run_create_otc(&mut app, &mut def, creator, None, &[native("luna", 10), cw20(50)], &[native("btc", 5)], fee.clone()).unwrap();
+
+    run_execute_otc(&mut app, &mut def, executor, 1, fee.clone()).unwrap();
+
+    let filter = |offer: Option<&str>, ask: Option<&str>, status: Option<QueryPositionsFilterStatus>| QueryPositionsFilter {
+        owner: None,
+        executor: None,
+        status,
+        offer_asset: offer.map(|val| val.to_string()),
+        ask_asset: ask.map(|val| val.to_string()),
+    };
+
+    // offer and ask assets, composable with the status
+
+    assert_eq!(vec![3, 1], qy_positions(&app, &def, filter(Some(token.as_str()), None, None), None, None).unwrap());
+    assert_eq!(vec![3], qy_positions(&app, &def, filter(Some(token.as_str()), None, Some(QueryPositionsFilterStatus::Pending)), None, None).unwrap());
+    assert_eq!(vec![1], qy_positions(&app, &def, filter(Some(token.as_str()), None, Some(QueryPositionsFilterStatus::Executed)), None, None).unwrap());
+    assert_eq!(vec![2, 1], qy_positions(&app, &def, filter(None, Some("usdc"), None), None, None).unwrap());
+    assert_eq!(vec![2], qy_positions(&app, &def, filter(None, Some("usdc"), Some(QueryPositionsFilterStatus::Pending)), None, None).unwrap());
+
+    // asset pairs
+
+    assert_eq!(vec![2], qy_positions(&app, &def, filter(Some(collection.as_str()), Some("usdc"), None), None, None).unwrap());
+    assert_eq!(vec![3], qy_positions(&app, &def, filter(Some("luna"), Some("btc"), Some(QueryPositionsFilterStatus::Pending)), None, None).unwrap());
+    assert!(qy_positions(&app, &def, filter(Some(collection.as_str()), Some("btc"), None), None, None).unwrap().is_empty());
+
+    // pagination
+
+    assert_eq!(vec![3], qy_positions(&app, &def, filter(Some(token.as_str()), None, None), None, Some(1)).unwrap());
+    assert_eq!(vec![1], qy_positions(&app, &def, filter(Some(token.as_str()), None, None), Some(3), None).unwrap());
+
+    // asset filters can't be combined with owner or executor
+
+    qy_positions(&app, &def, QueryPositionsFilter { owner: Some(creator.to_string()), ..filter(Some(token.as_str()), None, None) }, None, None).unwrap_err();
+
+    // removed positions are removed from the indexes
+
+    app.execute_contract(creator.into_unchecked_addr(), otc.clone(), &ExecuteMsg::CancelOtc(CancelOtcMsg { id: 3 }), &[]).unwrap();
+
+    assert_eq!(vec![1], qy_positions(&app, &def, filter(Some(token.as_str()), None, None), None, None).unwrap());
+    assert!(qy_positions(&app, &def, filter(Some("luna"), Some("btc"), None), None, None).unwrap().is_empty());
+}
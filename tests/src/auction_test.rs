@@ -0,0 +1,145 @@
+use cosmwasm_std::Coin;
+use otcer_pkg::otcer::{
+    definitions::{OtcItemInfo, OtcPositionStatus},
+    msgs::{AuctionRegistration, OtcItemRegistration},
+};
+
+use crate::helper::{
+    create_token, increase_allowance, increase_time, mint_token, qy_balance_cw20,
+    qy_balance_native, qy_balance_nft, qy_otc_active_position, run_bid_auction, run_create_auction,
+    run_execute_otc, run_settle_auction, startup, Def, TokenType,
+};
+
+#[test]
+#[rustfmt::skip]
+pub fn test_english_auction() {
+    let mut def = Def::new();
+
+    let mut app = startup(&mut def);
+
+    let creator = "creator";
+    let bidder_1 = "bidder_1";
+    let bidder_2 = "bidder_2";
+
+    let fee = def.get_native_fee();
+
+    let offer_nft_id = "1";
+    let offer_nft_addr = create_token(&mut app, &mut def, "NftOffer", TokenType::Cw721, vec![(creator, offer_nft_id)]);
+    increase_allowance(&mut app, creator, def.addr_otc.clone().unwrap().as_ref(), &offer_nft_addr, TokenType::Cw721, offer_nft_id);
+
+    let bid_denom = "btc";
+    mint_token(&mut app, &mut def, bidder_1, (bid_denom, TokenType::Native), "1000");
+    mint_token(&mut app, &mut def, bidder_2, (bid_denom, TokenType::Native), "1000");
+
+    let end_time = app.block_info().time.seconds() + 1_000;
+
+    let offer_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Cw721 { contract: offer_nft_addr.clone(), token_id: offer_nft_id.to_string() }, vesting: None, pricing: None },
+    ];
+
+    let auction = AuctionRegistration {
+        reserve: OtcItemInfo::Token { denom: bid_denom.to_string(), amount: 100_u128.into() },
+        min_increment: 10_u128.into(),
+        end_time,
+        extension: 100,
+    };
+
+    mint_token(&mut app, &mut def, creator, (&fee[0].denom, TokenType::Native), &fee[0].amount.to_string());
+    run_create_auction(&mut app, &mut def, creator, &offer_items, auction, fee.clone()).unwrap();
+
+    // fixed price execution is not allowed
+
+    run_execute_otc(&mut app, &mut def, bidder_1, 1, vec![]).unwrap_err();
+
+    // bids under reserve or under min increment fail
+
+    run_bid_auction(&mut app, &mut def, bidder_1, 1, 99, vec![Coin::new(99, bid_denom)]).unwrap_err();
+    run_bid_auction(&mut app, &mut def, creator, 1, 100, vec![Coin::new(100, bid_denom)]).unwrap_err();
+    run_bid_auction(&mut app, &mut def, bidder_1, 1, 100, vec![Coin::new(100, bid_denom)]).unwrap();
+    run_bid_auction(&mut app, &mut def, bidder_2, 1, 105, vec![Coin::new(105, bid_denom)]).unwrap_err();
+
+    // outbid bidder is refunded
+
+    run_bid_auction(&mut app, &mut def, bidder_2, 1, 110, vec![Coin::new(110, bid_denom)]).unwrap();
+    assert_eq!(1000, qy_balance_native(&app, bid_denom, bidder_1).u128());
+    assert_eq!(890, qy_balance_native(&app, bid_denom, bidder_2).u128());
+
+    // anti-sniping extension
+
+    increase_time(&mut app, 950);
+    run_settle_auction(&mut app, &mut def, bidder_2, 1).unwrap_err();
+    run_bid_auction(&mut app, &mut def, bidder_1, 1, 200, vec![Coin::new(200, bid_denom)]).unwrap();
+
+    let position = qy_otc_active_position(&app, &def, 1).unwrap();
+    assert_eq!(app.block_info().time.seconds() + 100, position.auction.unwrap().end_time);
+    assert_eq!(1000, qy_balance_native(&app, bid_denom, bidder_2).u128());
+
+    increase_time(&mut app, 60);
+    run_settle_auction(&mut app, &mut def, bidder_2, 1).unwrap_err();
+
+    // settle
+
+    increase_time(&mut app, 40);
+    run_settle_auction(&mut app, &mut def, bidder_2, 1).unwrap();
+
+    assert!(qy_balance_nft(&app, &offer_nft_addr, offer_nft_id, bidder_1));
+    assert_eq!(190, qy_balance_native(&app, bid_denom, creator).u128());
+    assert_eq!(10, qy_balance_native(&app, bid_denom, def.fee_collector).u128());
+
+    let position = qy_otc_active_position(&app, &def, 1).unwrap();
+    assert!(matches!(position.status, OtcPositionStatus::Executed(..)));
+    assert_eq!(bidder_1, position.executor.unwrap().as_str());
+
+    run_settle_auction(&mut app, &mut def, bidder_2, 1).unwrap_err();
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_english_auction_cw20_without_bids() {
+    let mut def = Def::new();
+
+    let mut app = startup(&mut def);
+
+    let creator = "creator";
+    let bidder = "bidder";
+
+    let fee = def.get_native_fee();
+
+    let offer_denom = "luna";
+    mint_token(&mut app, &mut def, creator, (offer_denom, TokenType::Native), "1000");
+
+    let bid_cw20_addr = create_token(&mut app, &mut def, "TokenBid", TokenType::Cw20, vec![(bidder, "1000")]);
+
+    let offer_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: offer_denom.to_string(), amount: 500_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    let auction = AuctionRegistration {
+        reserve: OtcItemInfo::Cw20 { contract: bid_cw20_addr.clone(), amount: 100_u128.into() },
+        min_increment: 1_u128.into(),
+        end_time: app.block_info().time.seconds() + 1_000,
+        extension: 0,
+    };
+
+    mint_token(&mut app, &mut def, creator, (&fee[0].denom, TokenType::Native), &(fee[0].amount.u128() * 2).to_string());
+    run_create_auction(&mut app, &mut def, creator, &offer_items, auction.clone(), fee.clone()).unwrap();
+    run_create_auction(&mut app, &mut def, creator, &offer_items, auction, fee.clone()).unwrap();
+
+    // cw20 bid is pulled from the allowance
+
+    run_bid_auction(&mut app, &mut def, bidder, 2, 300, vec![]).unwrap_err();
+    increase_allowance(&mut app, bidder, def.addr_otc.clone().unwrap().as_ref(), &bid_cw20_addr, TokenType::Cw20, "300");
+    run_bid_auction(&mut app, &mut def, bidder, 2, 300, vec![]).unwrap();
+    assert_eq!(700, qy_balance_cw20(&app, &bid_cw20_addr, bidder).u128());
+
+    // auction without bids returns the offer to the owner
+
+    increase_time(&mut app, 1_000);
+    run_settle_auction(&mut app, &mut def, bidder, 1).unwrap();
+    assert_eq!(500, qy_balance_native(&app, offer_denom, creator).u128());
+    qy_otc_active_position(&app, &def, 1).unwrap_err();
+
+    run_settle_auction(&mut app, &mut def, bidder, 2).unwrap();
+    assert_eq!(285, qy_balance_cw20(&app, &bid_cw20_addr, creator).u128());
+    assert_eq!(15, qy_balance_cw20(&app, &bid_cw20_addr, def.fee_collector).u128());
+}
@@ -0,0 +1,112 @@
+use otcer_pkg::otcer::{
+    definitions::{OtcItemInfo, OtcPositionStatus},
+    msgs::{OtcItemRegistration, VestingInfoRegistration},
+};
+
+use crate::helper::{
+    create_token, increase_allowance, increase_time, mint_token, qy_balance_cw20,
+    qy_balance_native, qy_bids, qy_otc_active_position, qy_solvency, run_accept_bid, run_claim_otc,
+    run_create_otc, run_place_bid, run_withdraw_bid, startup, Def, TokenType,
+};
+
+#[test]
+#[rustfmt::skip]
+pub fn test_open_bids() {
+    let mut def = Def::new();
+
+    let mut app = startup(&mut def);
+
+    let creator = "creator";
+    let bidder_1 = "bidder_1";
+    let bidder_2 = "bidder_2";
+    let bidder_3 = "bidder_3";
+
+    let fee = def.get_native_fee();
+
+    let offer_denom = "luna";
+    let bid_denom = "btc";
+
+    mint_token(&mut app, &mut def, creator, (offer_denom, TokenType::Native), "100");
+    mint_token(&mut app, &mut def, creator, (&fee[0].denom, TokenType::Native), &fee[0].amount.to_string());
+    mint_token(&mut app, &mut def, bidder_2, (bid_denom, TokenType::Native), "500");
+    mint_token(&mut app, &mut def, bidder_3, (bid_denom, TokenType::Native), "400");
+
+    for bidder in [bidder_1, bidder_2, bidder_3] {
+        mint_token(&mut app, &mut def, bidder, (&fee[0].denom, TokenType::Native), &fee[0].amount.to_string());
+    }
+
+    let bid_cw20_addr = create_token(&mut app, &mut def, "TokenBid", TokenType::Cw20, vec![(bidder_1, "300")]);
+    increase_allowance(&mut app, bidder_1, def.addr_otc.clone().unwrap().as_ref(), &bid_cw20_addr, TokenType::Cw20, "300");
+
+    let offer_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: offer_denom.to_string(), amount: 100_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    let ask_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: bid_denom.to_string(), amount: 1_000_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    run_create_otc(&mut app, &mut def, creator, None, &offer_items, &ask_items, fee.clone()).unwrap();
+
+    let bid_1 = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Cw20 { contract: bid_cw20_addr.clone(), amount: 300_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    let bid_2 = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: bid_denom.to_string(), amount: 500_u128.into() }, vesting: Some(VestingInfoRegistration { cliff: Some(100), vesting: None, tranches: None }), pricing: None },
+    ];
+
+    let bid_3 = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: bid_denom.to_string(), amount: 400_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    // owner can't bid and the fee is required
+
+    run_place_bid(&mut app, &mut def, creator, 1, &bid_3, vec![]).unwrap_err();
+    run_place_bid(&mut app, &mut def, bidder_3, 1, &bid_3, vec![]).unwrap_err();
+
+    run_place_bid(&mut app, &mut def, bidder_1, 1, &bid_1, fee.clone()).unwrap();
+    run_place_bid(&mut app, &mut def, bidder_2, 1, &bid_2, fee.clone()).unwrap();
+    run_place_bid(&mut app, &mut def, bidder_3, 1, &bid_3, fee.clone()).unwrap();
+
+    assert_eq!(3, qy_bids(&app, &def, 1).unwrap().len());
+    assert_eq!(0, qy_balance_cw20(&app, &bid_cw20_addr, bidder_1).u128());
+
+    // withdraw
+
+    run_withdraw_bid(&mut app, &mut def, bidder_2, 1, 3).unwrap_err();
+    run_withdraw_bid(&mut app, &mut def, bidder_3, 1, 3).unwrap();
+    assert_eq!(400, qy_balance_native(&app, bid_denom, bidder_3).u128());
+    assert_eq!(fee[0].amount, qy_balance_native(&app, &fee[0].denom, bidder_3));
+    assert_eq!(2, qy_bids(&app, &def, 1).unwrap().len());
+
+    // accept
+
+    run_accept_bid(&mut app, &mut def, bidder_2, 1, 2).unwrap_err();
+    run_accept_bid(&mut app, &mut def, creator, 1, 2).unwrap();
+
+    assert_eq!(100, qy_balance_native(&app, offer_denom, bidder_2).u128());
+    assert_eq!(300, qy_balance_cw20(&app, &bid_cw20_addr, bidder_1).u128());
+    assert_eq!(0, qy_bids(&app, &def, 1).unwrap().len());
+
+    // only the fee of the creator and of the accepted bid are collected, the others are refunded
+    assert_eq!(fee[0].amount.u128() * 2, qy_balance_native(&app, &fee[0].denom, def.fee_collector).u128());
+    assert_eq!(fee[0].amount, qy_balance_native(&app, &fee[0].denom, bidder_1));
+    assert_eq!(0, qy_balance_native(&app, &fee[0].denom, bidder_2).u128());
+    assert!(qy_solvency(&app, &def).solvent);
+
+    let position = qy_otc_active_position(&app, &def, 1).unwrap();
+    assert!(matches!(position.status, OtcPositionStatus::Vesting(..)));
+    assert_eq!(bidder_2, position.executor.unwrap().as_str());
+
+    // vesting of the accepted bid starts at the acceptance
+
+    run_claim_otc(&mut app, &mut def, creator, 1).unwrap_err();
+    increase_time(&mut app, 101);
+    run_claim_otc(&mut app, &mut def, creator, 1).unwrap();
+
+    assert_eq!(500, qy_balance_native(&app, bid_denom, creator).u128());
+
+    let position = qy_otc_active_position(&app, &def, 1).unwrap();
+    assert!(matches!(position.status, OtcPositionStatus::Executed(..)));
+}
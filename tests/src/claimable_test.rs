@@ -0,0 +1,85 @@
+use cosmwasm_std::Uint128;
+use otcer_pkg::otcer::{
+    definitions::{OtcItemInfo, OtcPositionStatus},
+    msgs::{OtcItemRegistration, VestingInfoRegistration},
+};
+
+use crate::helper::{
+    increase_time, mint_token, qy_balance_native, qy_claimable, qy_otc_active_position,
+    run_claim_otc, run_create_otc, run_execute_otc, startup, Def, TokenType,
+};
+
+#[test]
+#[rustfmt::skip]
+pub fn test_claimable() {
+    let mut def = Def::new();
+
+    let mut app = startup(&mut def);
+
+    let creator = "creator";
+    let executor = "executor";
+
+    let fee = def.get_native_fee();
+
+    mint_token(&mut app, &mut def, creator, ("luna", TokenType::Native), "1000");
+    mint_token(&mut app, &mut def, creator, (&fee[0].denom, TokenType::Native), &fee[0].amount.to_string());
+    mint_token(&mut app, &mut def, executor, ("btc", TokenType::Native), "100");
+    mint_token(&mut app, &mut def, executor, (&fee[0].denom, TokenType::Native), &fee[0].amount.to_string());
+
+    let offer_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: "luna".to_string(), amount: 1_000_u128.into() }, vesting: Some(VestingInfoRegistration { cliff: Some(100), vesting: Some(1_000), tranches: None }), pricing: None },
+    ];
+
+    let ask_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: "btc".to_string(), amount: 100_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    run_create_otc(&mut app, &mut def, creator, Some(executor), &offer_items, &ask_items, fee.clone()).unwrap();
+
+    // nothing vests while pending
+
+    let items = qy_claimable(&app, &def, 1, executor, Some(app.block_info().time.seconds() + 5_000)).unwrap();
+    assert_eq!(vec![(0, 1_000)], items.iter().map(|val| (val.claimable.u128(), val.remaining.u128())).collect::<Vec<_>>());
+
+    run_execute_otc(&mut app, &mut def, executor, 1, fee.clone()).unwrap();
+
+    let now = app.block_info().time.seconds();
+
+    let claimable = |app: &_, def: &_, at_time: Option<u64>| {
+        let items = qy_claimable(app, def, 1, executor, at_time).unwrap();
+        assert_eq!(1, items.len());
+        (items[0].claimable.u128(), items[0].remaining.u128())
+    };
+
+    // only parties can be simulated, the ask has no vesting
+
+    qy_claimable(&app, &def, 1, "stranger", None).unwrap_err();
+    assert!(qy_claimable(&app, &def, 1, creator, None).unwrap().is_empty());
+
+    assert_eq!((0, 1_000), claimable(&app, &def, None));
+    assert_eq!((0, 1_000), claimable(&app, &def, Some(now + 100)));
+    assert_eq!((500, 500), claimable(&app, &def, Some(now + 600)));
+    assert_eq!((1_000, 0), claimable(&app, &def, Some(now + 5_000)));
+
+    // the simulation matches the claim
+
+    increase_time(&mut app, 600);
+    run_claim_otc(&mut app, &mut def, executor, 1).unwrap();
+
+    assert_eq!(500, qy_balance_native(&app, "luna", executor).u128());
+    assert_eq!((0, 500), claimable(&app, &def, None));
+    assert_eq!((0, 500), claimable(&app, &def, Some(now)));
+    assert_eq!((200, 300), claimable(&app, &def, Some(now + 800)));
+
+    let items = qy_claimable(&app, &def, 1, executor, Some(now + 800)).unwrap();
+    assert_eq!(OtcItemInfo::Token { denom: "luna".to_string(), amount: Uint128::new(1_000) }, items[0].item_info);
+
+    // nothing is claimable once executed
+
+    increase_time(&mut app, 5_000);
+    run_claim_otc(&mut app, &mut def, executor, 1).unwrap();
+
+    assert!(matches!(qy_otc_active_position(&app, &def, 1).unwrap().status, OtcPositionStatus::Executed(..)));
+    assert_eq!((0, 0), claimable(&app, &def, None));
+    assert_eq!((0, 0), claimable(&app, &def, Some(now + 800)));
+}
@@ -0,0 +1,57 @@
+use cosmwasm_std::Decimal;
+use otcer_pkg::otcer::{
+    definitions::{FeeCollector, HookPolicy, OtcItemInfo},
+    msgs::{OtcItemRegistration, UpdateConfigMsg},
+};
+use rhaki_cw_plus::traits::IntoAddr;
+
+use crate::helper::{
+    mint_token, qy_config, qy_contract_info, run_create_otc, run_update_config, startup, Def,
+    TokenType,
+};
+
+#[test]
+#[rustfmt::skip]
+pub fn test_config_and_contract_info() {
+    let mut def = Def::new();
+
+    let mut app = startup(&mut def);
+
+    let creator = "creator";
+
+    let fee = def.get_native_fee();
+
+    let config = qy_config(&app, &def);
+
+    assert_eq!(def.owner, config.owner.as_str());
+    assert_eq!(def.otc_fee, config.fee);
+    assert_eq!(vec![FeeCollector { address: def.fee_collector.into_unchecked_addr(), weight: Decimal::one() }], config.fee_collectors);
+    assert_eq!(def.max_items_per_side, config.max_items_per_side);
+    assert_eq!(0, config.counter_otc);
+
+    // the counters and the updates are reported
+
+    mint_token(&mut app, &mut def, creator, ("luna", TokenType::Native), "100");
+    mint_token(&mut app, &mut def, creator, (&fee[0].denom, TokenType::Native), &fee[0].amount.to_string());
+
+    let items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: "luna".to_string(), amount: 100_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    run_create_otc(&mut app, &mut def, creator, None, &items, &items, fee.clone()).unwrap();
+    run_update_config(&mut app, &def, def.owner, UpdateConfigMsg { owner: None, fee: None, fee_collectors: None, auction_fee: None, price_oracle: None, hook_policy: Some(HookPolicy::Ignore), referral_share: None, excess_funds_policy: None, max_items_per_side: Some(3), listing_bond: None }).unwrap();
+
+    let config = qy_config(&app, &def);
+
+    assert_eq!(1, config.counter_otc);
+    assert_eq!(HookPolicy::Ignore, config.hook_policy);
+
+    // cw2 info is stored at instantiation
+
+    let info = qy_contract_info(&app, &def);
+
+    assert_eq!("otcer", info.contract);
+    assert_eq!(env!("CARGO_PKG_VERSION"), info.version);
+    assert_eq!(3, info.max_items_per_side);
+    assert!(info.default_query_limit <= info.max_query_limit);
+}
@@ -0,0 +1,121 @@
+use cosmwasm_std::{Coin, Decimal};
+use cw_multi_test::Executor;
+use otcer_pkg::otcer::{
+    definitions::{DisputeResolution, OtcItemInfo, OtcPositionStatus},
+    msgs::{
+        ArbiterRegistration, CancelOtcMsg, CreateOtcMsg, ExecuteMsg, OtcItemRegistration,
+        RaiseDisputeMsg, ResolveDisputeMsg, VestingInfoRegistration,
+    },
+};
+use rhaki_cw_plus::traits::IntoAddr;
+
+use crate::helper::{
+    increase_time, mint_token, qy_balance_native, qy_otc_active_position, qy_solvency,
+    run_claim_otc, run_create_otc, run_execute_otc, startup, Def, TokenType,
+};
+
+#[test]
+#[rustfmt::skip]
+pub fn test_dispute() {
+    let mut def = Def::new();
+
+    let mut app = startup(&mut def);
+
+    let creator = "creator";
+    let executor = "executor";
+    let arbiter = "arbiter";
+    let otc = def.addr_otc.clone().unwrap();
+
+    let fee = def.get_native_fee();
+
+    mint_token(&mut app, &mut def, creator, ("luna", TokenType::Native), "1100");
+    mint_token(&mut app, &mut def, creator, (&fee[0].denom, TokenType::Native), &(fee[0].amount.u128() * 3).to_string());
+    mint_token(&mut app, &mut def, executor, ("btc", TokenType::Native), "500");
+    mint_token(&mut app, &mut def, executor, (&fee[0].denom, TokenType::Native), &fee[0].amount.to_string());
+
+    let offer_items = |amount: u128, vesting: Option<VestingInfoRegistration>| vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: "luna".to_string(), amount: amount.into() }, vesting, pricing: None },
+    ];
+
+    let ask_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: "btc".to_string(), amount: 500_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    let create_msg = |offer: Vec<OtcItemRegistration>, arbiter: &str, arbiter_fee: u64| ExecuteMsg::CreateOtc(CreateOtcMsg {
+        executor: Some(executor.to_string()),
+        offer,
+        ask: ask_items.clone(),
+        auction: None,
+        hook: None,
+        referrer: None,
+        hash_lock: None,
+        arbiter: Some(ArbiterRegistration { address: arbiter.to_string(), fee: Decimal::percent(arbiter_fee) }),
+        milestones: None,
+    });
+
+    let raise_msg = |id: u64| ExecuteMsg::RaiseDispute(RaiseDisputeMsg { id });
+    let resolve_msg = |id: u64, resolution: DisputeResolution| ExecuteMsg::ResolveDispute(ResolveDisputeMsg { id, resolution });
+
+    let vesting = Some(VestingInfoRegistration { cliff: None, vesting: Some(1_000), tranches: None });
+
+    let mut coins = vec![Coin::new(1_000, "luna")];
+    coins.extend(fee.clone());
+
+    // the arbiter must be a third party with a fee < 1
+
+    app.execute_contract(creator.into_unchecked_addr(), otc.clone(), &create_msg(offer_items(1_000, vesting.clone()), executor, 10), &coins).unwrap_err();
+    app.execute_contract(creator.into_unchecked_addr(), otc.clone(), &create_msg(offer_items(1_000, vesting.clone()), arbiter, 100), &coins).unwrap_err();
+    app.execute_contract(creator.into_unchecked_addr(), otc.clone(), &create_msg(offer_items(1_000, vesting.clone()), arbiter, 10), &coins).unwrap();
+
+    run_execute_otc(&mut app, &mut def, executor, 1, fee.clone()).unwrap();
+
+    increase_time(&mut app, 400);
+    run_claim_otc(&mut app, &mut def, executor, 1).unwrap();
+
+    // raised by a party, freezes the position
+
+    app.execute_contract(arbiter.into_unchecked_addr(), otc.clone(), &resolve_msg(1, DisputeResolution::Settle), &[]).unwrap_err();
+    app.execute_contract("stranger".into_unchecked_addr(), otc.clone(), &raise_msg(1), &[]).unwrap_err();
+    app.execute_contract(creator.into_unchecked_addr(), otc.clone(), &raise_msg(1), &[]).unwrap();
+    app.execute_contract(executor.into_unchecked_addr(), otc.clone(), &raise_msg(1), &[]).unwrap_err();
+
+    increase_time(&mut app, 100);
+    run_claim_otc(&mut app, &mut def, executor, 1).unwrap_err();
+
+    // pro-rata on the unclaimed 600, after the 10% arbiter fee
+
+    app.execute_contract(creator.into_unchecked_addr(), otc.clone(), &resolve_msg(1, DisputeResolution::Refund), &[]).unwrap_err();
+    app.execute_contract(arbiter.into_unchecked_addr(), otc.clone(), &resolve_msg(1, DisputeResolution::ProRata { settle_share: Decimal::percent(50) }), &[]).unwrap();
+
+    assert_eq!(60, qy_balance_native(&app, "luna", arbiter).u128());
+    assert_eq!(670, qy_balance_native(&app, "luna", executor).u128());
+    assert_eq!(370, qy_balance_native(&app, "luna", creator).u128());
+    assert_eq!(500, qy_balance_native(&app, "btc", creator).u128());
+
+    assert!(matches!(qy_otc_active_position(&app, &def, 1).unwrap().status, OtcPositionStatus::Arbitrated(..)));
+
+    app.execute_contract(arbiter.into_unchecked_addr(), otc.clone(), &resolve_msg(1, DisputeResolution::Settle), &[]).unwrap_err();
+
+    // a pending position can only be refunded
+
+    let coins = vec![Coin::new(100, "luna"), fee[0].clone()];
+    app.execute_contract(creator.into_unchecked_addr(), otc.clone(), &create_msg(offer_items(100, None), arbiter, 10), &coins).unwrap();
+
+    app.execute_contract(executor.into_unchecked_addr(), otc.clone(), &raise_msg(2), &[]).unwrap();
+
+    run_execute_otc(&mut app, &mut def, executor, 2, vec![]).unwrap_err();
+    app.execute_contract(creator.into_unchecked_addr(), otc.clone(), &ExecuteMsg::CancelOtc(CancelOtcMsg { id: 2 }), &[]).unwrap_err();
+
+    app.execute_contract(arbiter.into_unchecked_addr(), otc.clone(), &resolve_msg(2, DisputeResolution::Settle), &[]).unwrap_err();
+    app.execute_contract(arbiter.into_unchecked_addr(), otc.clone(), &resolve_msg(2, DisputeResolution::Refund), &[]).unwrap();
+
+    assert_eq!(70, qy_balance_native(&app, "luna", arbiter).u128());
+    assert_eq!(360, qy_balance_native(&app, "luna", creator).u128());
+
+    // positions without an arbiter can't be disputed
+
+    run_create_otc(&mut app, &mut def, creator, Some(executor), &offer_items(100, None), &ask_items, fee.clone()).unwrap();
+    app.execute_contract(creator.into_unchecked_addr(), otc.clone(), &raise_msg(3), &[]).unwrap_err();
+
+    assert!(qy_solvency(&app, &def).solvent);
+}
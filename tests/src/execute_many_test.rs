@@ -0,0 +1,84 @@
+use cosmwasm_std::Coin;
+use otcer_pkg::otcer::{
+    definitions::{OtcItemInfo, OtcPositionStatus},
+    msgs::OtcItemRegistration,
+};
+
+use crate::helper::{
+    create_token, increase_allowance, mint_token, qy_balance_cw20, qy_balance_native,
+    qy_otc_active_position, qy_solvency, run_create_otc, run_execute_many, startup, Def, TokenType,
+};
+
+#[test]
+#[rustfmt::skip]
+pub fn test_execute_many() {
+    let mut def = Def::new();
+
+    let mut app = startup(&mut def);
+
+    let creator_1 = "creator_1";
+    let creator_2 = "creator_2";
+    let executor = "executor";
+    let otc = def.addr_otc.clone().unwrap();
+
+    let fee = def.get_native_fee();
+    let fee_amount = fee[0].amount.u128();
+
+    let token = create_token(&mut app, &mut def, "Token", TokenType::Cw20, vec![(executor, "200")]);
+
+    for creator in [creator_1, creator_2] {
+        mint_token(&mut app, &mut def, creator, ("luna", TokenType::Native), "100");
+        mint_token(&mut app, &mut def, creator, (&fee[0].denom, TokenType::Native), &fee[0].amount.to_string());
+    }
+
+    mint_token(&mut app, &mut def, executor, ("btc", TokenType::Native), "150");
+    mint_token(&mut app, &mut def, executor, (&fee[0].denom, TokenType::Native), &(fee_amount * 2).to_string());
+
+    let offer_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: "luna".to_string(), amount: 100_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    let ask_btc = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: "btc".to_string(), amount: 150_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    let ask_cw20 = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Cw20 { contract: token.clone(), amount: 200_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    run_create_otc(&mut app, &mut def, creator_1, None, &offer_items, &ask_btc, fee.clone()).unwrap();
+    run_create_otc(&mut app, &mut def, creator_2, None, &offer_items, &ask_cw20, fee.clone()).unwrap();
+
+    let coins = vec![Coin::new(150, "btc"), Coin::new(fee_amount * 2, &fee[0].denom)];
+
+    // empty or repeated ids
+
+    run_execute_many(&mut app, &mut def, executor, vec![], coins.clone()).unwrap_err();
+    run_execute_many(&mut app, &mut def, executor, vec![1, 1], coins.clone()).unwrap_err();
+
+    // a single fee for two positions is not enough
+
+    increase_allowance(&mut app, executor, otc.as_str(), &token, TokenType::Cw20, "200");
+    run_execute_many(&mut app, &mut def, executor, vec![1, 2], vec![Coin::new(150, "btc"), fee[0].clone()]).unwrap_err();
+
+    // a failure on one position reverts the others
+
+    run_execute_many(&mut app, &mut def, executor, vec![1, 2, 3], coins.clone()).unwrap_err();
+
+    assert_eq!(150, qy_balance_native(&app, "btc", executor).u128());
+    assert!(matches!(qy_otc_active_position(&app, &def, 1).unwrap().status, OtcPositionStatus::Pending));
+
+    run_execute_many(&mut app, &mut def, executor, vec![1, 2], coins).unwrap();
+
+    assert_eq!(200, qy_balance_native(&app, "luna", executor).u128());
+    assert_eq!(150, qy_balance_native(&app, "btc", creator_1).u128());
+    assert_eq!(200, qy_balance_cw20(&app, &token, creator_2).u128());
+    assert_eq!(fee_amount * 4, qy_balance_native(&app, &fee[0].denom, def.fee_collector).u128());
+    assert_eq!(0, qy_balance_native(&app, "luna", otc.as_str()).u128());
+
+    for id in [1, 2] {
+        assert!(matches!(qy_otc_active_position(&app, &def, id).unwrap().status, OtcPositionStatus::Executed(..)));
+    }
+
+    assert!(qy_solvency(&app, &def).solvent);
+}
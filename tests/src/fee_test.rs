@@ -0,0 +1,263 @@
+use cosmwasm_std::{Coin, Decimal, Uint128};
+use cw_multi_test::Executor;
+use otcer_pkg::otcer::{
+    definitions::{ExcessFundsPolicy, OtcItemInfo},
+    msgs::{
+        CancelNoncesMsg, CreateOtcMsg, ExecuteMsg, ExecuteOtcMsg, FeeCollectorRegistration,
+        OtcItemRegistration, QueryMsg, RecoverStuckFundsMsg, UpdateConfigMsg,
+    },
+};
+use rhaki_cw_plus::traits::IntoAddr;
+
+use crate::helper::{
+    create_token, increase_allowance, mint_token, qy_balance_cw20, qy_balance_native,
+    run_create_otc, run_execute_otc, run_update_config, startup, Def, TokenType,
+};
+
+#[test]
+#[rustfmt::skip]
+pub fn test_referral_fee() {
+    let mut def = Def::new();
+
+    let mut app = startup(&mut def);
+
+    let creator = "creator";
+    let executor = "executor";
+    let referrer = "referrer";
+
+    let fee = def.get_native_fee();
+
+    let offer_denom = "luna";
+    let ask_denom = "btc";
+
+    mint_token(&mut app, &mut def, creator, (offer_denom, TokenType::Native), "100");
+    mint_token(&mut app, &mut def, creator, (&fee[0].denom, TokenType::Native), &fee[0].amount.to_string());
+    mint_token(&mut app, &mut def, executor, (ask_denom, TokenType::Native), "100");
+    mint_token(&mut app, &mut def, executor, (&fee[0].denom, TokenType::Native), &fee[0].amount.to_string());
+
+    let offer_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: offer_denom.to_string(), amount: 100_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    let ask_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: ask_denom.to_string(), amount: 100_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    let create_msg = |referrer: &str| ExecuteMsg::CreateOtc(CreateOtcMsg {
+        executor: Some(executor.to_string()),
+        offer: offer_items.clone(),
+        ask: ask_items.clone(),
+        auction: None,
+        hook: None,
+        referrer: Some(referrer.to_string()),
+        hash_lock: None,
+        arbiter: None,
+        milestones: None,
+    });
+
+    // self referral is not allowed
+
+    app.execute_contract(creator.into_unchecked_addr(), def.addr_otc.clone().unwrap(), &create_msg(creator), &[Coin::new(100, offer_denom), fee[0].clone()]).unwrap_err();
+    app.execute_contract(creator.into_unchecked_addr(), def.addr_otc.clone().unwrap(), &create_msg(referrer), &[Coin::new(100, offer_denom), fee[0].clone()]).unwrap();
+
+    app.execute_contract(
+        executor.into_unchecked_addr(),
+        def.addr_otc.clone().unwrap(),
+        &ExecuteMsg::ExecuteOtc(ExecuteOtcMsg { id: 1, referrer: Some(referrer.to_string()), secret: None }),
+        &[Coin::new(100, ask_denom), fee[0].clone()],
+    ).unwrap();
+
+    let referral_amount = fee[0].amount * def.referral_share;
+
+    assert_eq!(referral_amount.u128() * 2, qy_balance_native(&app, &fee[0].denom, referrer).u128());
+    assert_eq!((fee[0].amount - referral_amount).u128() * 2, qy_balance_native(&app, &fee[0].denom, def.fee_collector).u128());
+
+    let totals: Vec<OtcItemInfo> = app.wrap().query_wasm_smart(def.addr_otc.clone().unwrap(), &QueryMsg::ReferralTotals { referrer: referrer.to_string() }).unwrap();
+
+    assert_eq!(vec![OtcItemInfo::Token { denom: fee[0].denom.clone(), amount: referral_amount * Uint128::new(2) }], totals);
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_weighted_fee_collectors() {
+    let mut def = Def::new();
+    def.otc_fee = vec![OtcItemInfo::Token { denom: "uluna".to_string(), amount: 101_u128.into() }];
+
+    let mut app = startup(&mut def);
+
+    let creator = "creator";
+
+    let fee = def.get_native_fee();
+
+    let update_msg = |weights: [u64; 3]| UpdateConfigMsg {
+        owner: None,
+        fee: None,
+        fee_collectors: Some(vec![
+            FeeCollectorRegistration { address: "treasury".to_string(), weight: Decimal::percent(weights[0]) },
+            FeeCollectorRegistration { address: "insurance".to_string(), weight: Decimal::percent(weights[1]) },
+            FeeCollectorRegistration { address: "stakers".to_string(), weight: Decimal::percent(weights[2]) },
+        ]),
+        auction_fee: None,
+        price_oracle: None,
+        hook_policy: None,
+        referral_share: None,
+        excess_funds_policy: None,
+        max_items_per_side: None,
+        listing_bond: None,
+    };
+
+    // weights must sum to 1
+
+    run_update_config(&mut app, &def, def.owner, update_msg([50, 30, 30])).unwrap_err();
+    run_update_config(&mut app, &def, def.owner, update_msg([50, 50, 0])).unwrap_err();
+    run_update_config(&mut app, &def, def.owner, update_msg([50, 30, 20])).unwrap();
+
+    let offer_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: "luna".to_string(), amount: 100_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    mint_token(&mut app, &mut def, creator, ("luna", TokenType::Native), "100");
+    mint_token(&mut app, &mut def, creator, (&fee[0].denom, TokenType::Native), &fee[0].amount.to_string());
+    run_create_otc(&mut app, &mut def, creator, None, &offer_items, &offer_items, fee.clone()).unwrap();
+
+    // rounding remainder goes to the first collector
+
+    assert_eq!(51, qy_balance_native(&app, &fee[0].denom, "treasury").u128());
+    assert_eq!(30, qy_balance_native(&app, &fee[0].denom, "insurance").u128());
+    assert_eq!(20, qy_balance_native(&app, &fee[0].denom, "stakers").u128());
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_cw20_fee_does_not_touch_escrow() {
+    let mut def = Def::new();
+
+    let mut app = startup(&mut def);
+
+    let creator = "creator";
+    let executor = "executor";
+    let otc = def.addr_otc.clone().unwrap();
+
+    let token = create_token(&mut app, &mut def, "Token", TokenType::Cw20, vec![(creator, "110"), (executor, "10")]);
+
+    let update_msg = |fee: OtcItemInfo| UpdateConfigMsg {
+        owner: None,
+        fee: Some(vec![fee]),
+        fee_collectors: None,
+        auction_fee: None,
+        price_oracle: None,
+        hook_policy: None,
+        referral_share: None,
+        excess_funds_policy: None,
+        max_items_per_side: None,
+        listing_bond: None,
+    };
+
+    // cw721 fee is rejected
+
+    run_update_config(&mut app, &def, def.owner, update_msg(OtcItemInfo::Cw721 { contract: token.clone(), token_id: "1".to_string() })).unwrap_err();
+    run_update_config(&mut app, &def, def.owner, update_msg(OtcItemInfo::Cw20 { contract: token.clone(), amount: 10_u128.into() })).unwrap();
+
+    let offer_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Cw20 { contract: token.clone(), amount: 100_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    let ask_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: "btc".to_string(), amount: 50_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    // allowance covers only the escrow, the fee can't be paid
+
+    increase_allowance(&mut app, creator, otc.as_str(), &token, TokenType::Cw20, "100");
+    run_create_otc(&mut app, &mut def, creator, Some(executor), &offer_items, &ask_items, vec![]).unwrap_err();
+
+    increase_allowance(&mut app, creator, otc.as_str(), &token, TokenType::Cw20, "10");
+    run_create_otc(&mut app, &mut def, creator, Some(executor), &offer_items, &ask_items, vec![]).unwrap();
+
+    assert_eq!(100, qy_balance_cw20(&app, &token, otc.as_str()).u128());
+    assert_eq!(10, qy_balance_cw20(&app, &token, def.fee_collector).u128());
+    assert_eq!(0, qy_balance_cw20(&app, &token, creator).u128());
+
+    // the executor pays the fee from its own balance
+
+    mint_token(&mut app, &mut def, executor, ("btc", TokenType::Native), "50");
+    increase_allowance(&mut app, executor, otc.as_str(), &token, TokenType::Cw20, "10");
+    run_execute_otc(&mut app, &mut def, executor, 1, vec![]).unwrap();
+
+    assert_eq!(0, qy_balance_cw20(&app, &token, otc.as_str()).u128());
+    assert_eq!(100, qy_balance_cw20(&app, &token, executor).u128());
+    assert_eq!(20, qy_balance_cw20(&app, &token, def.fee_collector).u128());
+    assert_eq!(50, qy_balance_native(&app, "btc", creator).u128());
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_excess_funds() {
+    let mut def = Def::new();
+
+    let mut app = startup(&mut def);
+
+    let creator = "creator";
+    let otc = def.addr_otc.clone().unwrap();
+
+    let fee = def.get_native_fee();
+
+    mint_token(&mut app, &mut def, creator, ("luna", TokenType::Native), "330");
+    mint_token(&mut app, &mut def, creator, ("junk", TokenType::Native), "50");
+    mint_token(&mut app, &mut def, creator, (&fee[0].denom, TokenType::Native), &(fee[0].amount.u128() * 2 + 10).to_string());
+
+    let offer_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: "luna".to_string(), amount: 100_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    let ask_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: "btc".to_string(), amount: 50_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    // overpayment and unrelated denoms are refunded
+
+    let mut extra = fee.clone();
+    extra[0].amount += Uint128::new(10);
+    extra.push(Coin::new(50, "junk"));
+
+    run_create_otc(&mut app, &mut def, creator, None, &offer_items, &ask_items, extra.clone()).unwrap();
+
+    assert_eq!(50, qy_balance_native(&app, "junk", creator).u128());
+    assert_eq!(fee[0].amount.u128() + 10, qy_balance_native(&app, &fee[0].denom, creator).u128());
+    assert_eq!(0, qy_balance_native(&app, &fee[0].denom, otc.as_str()).u128());
+
+    // as the funds sent to an action that doesn't require any
+
+    let cancel_nonces = ExecuteMsg::CancelNonces(CancelNoncesMsg { nonces: vec![1] });
+
+    app.execute_contract(creator.into_unchecked_addr(), otc.clone(), &cancel_nonces, &[Coin::new(50, "junk")]).unwrap();
+    assert_eq!(50, qy_balance_native(&app, "junk", creator).u128());
+    assert_eq!(0, qy_balance_native(&app, "junk", otc.as_str()).u128());
+
+    // with ExcessFundsPolicy::Reject the action fails
+
+    run_update_config(&mut app, &def, def.owner, UpdateConfigMsg { owner: None, fee: None, fee_collectors: None, auction_fee: None, price_oracle: None, hook_policy: None, referral_share: None, excess_funds_policy: Some(ExcessFundsPolicy::Reject), max_items_per_side: None, listing_bond: None }).unwrap();
+
+    run_create_otc(&mut app, &mut def, creator, None, &offer_items, &ask_items, vec![Coin::new(10, fee[0].denom.clone()), Coin::new(100, &fee[0].denom)]).unwrap_err();
+    run_create_otc(&mut app, &mut def, creator, None, &offer_items, &ask_items, fee.clone()).unwrap();
+    app.execute_contract(creator.into_unchecked_addr(), otc.clone(), &cancel_nonces, &[Coin::new(50, "junk")]).unwrap_err();
+
+    // funds sent directly to the contract can be recovered, the escrow can't
+
+    app.send_tokens(creator.into_unchecked_addr(), otc.clone(), &[Coin::new(30, "luna")]).unwrap();
+
+    let recover_msg = |amount: u128| ExecuteMsg::RecoverStuckFunds(RecoverStuckFundsMsg {
+        asset: OtcItemInfo::Token { denom: "luna".to_string(), amount: amount.into() },
+        recipient: "treasury".to_string(),
+    });
+
+    app.execute_contract(creator.into_unchecked_addr(), otc.clone(), &recover_msg(30), &[]).unwrap_err();
+    app.execute_contract(def.owner.into_unchecked_addr(), otc.clone(), &recover_msg(31), &[]).unwrap_err();
+    let owner = def.owner;
+    mint_token(&mut app, &mut def, owner, ("luna", TokenType::Native), "10");
+    app.execute_contract(def.owner.into_unchecked_addr(), otc.clone(), &recover_msg(30), &[Coin::new(10, "luna")]).unwrap_err();
+    app.execute_contract(def.owner.into_unchecked_addr(), otc.clone(), &recover_msg(30), &[]).unwrap();
+
+    assert_eq!(30, qy_balance_native(&app, "luna", "treasury").u128());
+    assert_eq!(200, qy_balance_native(&app, "luna", otc.as_str()).u128());
+}
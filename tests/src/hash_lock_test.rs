@@ -0,0 +1,102 @@
+use cosmwasm_std::{Binary, Coin, HexBinary};
+use cw_multi_test::{App, AppResponse, Executor};
+use otcer::response::ContractError;
+use otcer_pkg::otcer::{
+    definitions::{OtcItemInfo, OtcPositionStatus},
+    msgs::{CommitExecutionMsg, CreateOtcMsg, ExecuteMsg, ExecuteOtcMsg, OtcItemRegistration},
+};
+use rhaki_cw_plus::traits::IntoAddr;
+use sha2::{Digest, Sha256};
+
+use crate::helper::{
+    mint_token, qy_balance_native, qy_otc_active_position, run_place_bid, startup, Def, TokenType,
+};
+
+fn commitment(secret: &[u8], sender: &str) -> HexBinary {
+    HexBinary::from(
+        Sha256::new()
+            .chain_update(secret)
+            .chain_update(sender.as_bytes())
+            .finalize()
+            .to_vec(),
+    )
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_hash_lock() {
+    let mut def = Def::new();
+
+    let mut app = startup(&mut def);
+
+    let creator = "creator";
+    let executor = "executor";
+    let watcher = "watcher";
+    let otc = def.addr_otc.clone().unwrap();
+
+    let fee = def.get_native_fee();
+    let secret = b"link secret";
+
+    mint_token(&mut app, &mut def, creator, ("luna", TokenType::Native), "100");
+    mint_token(&mut app, &mut def, creator, (&fee[0].denom, TokenType::Native), &fee[0].amount.to_string());
+
+    for user in [executor, watcher] {
+        mint_token(&mut app, &mut def, user, ("btc", TokenType::Native), "50");
+        mint_token(&mut app, &mut def, user, (&fee[0].denom, TokenType::Native), &(fee[0].amount.u128() * 2).to_string());
+    }
+
+    let create_msg = |hash_lock: Vec<u8>| ExecuteMsg::CreateOtc(CreateOtcMsg {
+        executor: None,
+        offer: vec![OtcItemRegistration { item_info: OtcItemInfo::Token { denom: "luna".to_string(), amount: 100_u128.into() }, vesting: None, pricing: None }],
+        ask: vec![OtcItemRegistration { item_info: OtcItemInfo::Token { denom: "btc".to_string(), amount: 50_u128.into() }, vesting: None, pricing: None }],
+        auction: None,
+        hook: None,
+        referrer: None,
+        hash_lock: Some(HexBinary::from(hash_lock)),
+        arbiter: None,
+        milestones: None,
+    });
+
+    app.execute_contract(creator.into_unchecked_addr(), otc.clone(), &create_msg(b"not a hash".to_vec()), &[Coin::new(100, "luna"), fee[0].clone()]).unwrap_err();
+    app.execute_contract(creator.into_unchecked_addr(), otc.clone(), &create_msg(Sha256::digest(secret).to_vec()), &[Coin::new(100, "luna"), fee[0].clone()]).unwrap();
+
+    let funds = [Coin::new(50, "btc"), fee[0].clone()];
+
+    let execute = |app: &mut App, sender: &str, secret: Option<&[u8]>| -> anyhow::Result<AppResponse> {
+        app.execute_contract(sender.into_unchecked_addr(), otc.clone(), &ExecuteMsg::ExecuteOtc(ExecuteOtcMsg { id: 1, referrer: None, secret: secret.map(Binary::from) }), &funds)
+    };
+
+    let commit = |app: &mut App, sender: &str, commitment: HexBinary| -> anyhow::Result<AppResponse> {
+        app.execute_contract(sender.into_unchecked_addr(), otc.clone(), &ExecuteMsg::CommitExecution(CommitExecutionMsg { id: 1, commitment }), &[])
+    };
+
+    // bids would bypass the hash lock
+
+    run_place_bid(&mut app, &mut def, executor, 1, &[OtcItemRegistration { item_info: OtcItemInfo::Token { denom: "btc".to_string(), amount: 50_u128.into() }, vesting: None, pricing: None }], fee.clone()).unwrap_err();
+
+    // secret is required, revealing it without a previous commitment fails
+
+    assert_eq!(ContractError::InvalidSecret {}.to_string(), execute(&mut app, executor, None).unwrap_err().root_cause().to_string());
+    assert_eq!(ContractError::MissingCommitment {}.to_string(), execute(&mut app, executor, Some(secret)).unwrap_err().root_cause().to_string());
+
+    // commit and reveal in the same block
+
+    commit(&mut app, executor, commitment(secret, executor)).unwrap();
+    assert_eq!(ContractError::MissingCommitment {}.to_string(), execute(&mut app, executor, Some(secret)).unwrap_err().root_cause().to_string());
+
+    app.update_block(|block| block.height += 1);
+
+    // a watcher copying the commitment can't use the revealed secret
+
+    commit(&mut app, watcher, commitment(secret, executor)).unwrap();
+    app.update_block(|block| block.height += 1);
+
+    assert_eq!(ContractError::InvalidSecret {}.to_string(), execute(&mut app, watcher, Some(secret)).unwrap_err().root_cause().to_string());
+    assert_eq!(ContractError::InvalidSecret {}.to_string(), execute(&mut app, executor, Some(b"wrong secret")).unwrap_err().root_cause().to_string());
+
+    execute(&mut app, executor, Some(secret)).unwrap();
+
+    assert_eq!(100, qy_balance_native(&app, "luna", executor).u128());
+    assert_eq!(50, qy_balance_native(&app, "btc", creator).u128());
+    assert!(matches!(qy_otc_active_position(&app, &def, 1).unwrap().status, OtcPositionStatus::Executed(..)));
+}
@@ -1,11 +1,21 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Coin, Empty, StdResult, Uint128};
+use cosmwasm_std::{Addr, Coin, Decimal, Empty, StdResult, Uint128};
 use cw20::{BalanceResponse, Cw20Coin};
 use cw721::OwnerOfResponse;
-use cw_multi_test::{App, AppResponse, Executor};
+use cw_multi_test::{App, AppResponse, ContractWrapper, Executor};
 use otcer_pkg::otcer::{
-    definitions::{OtcItem, OtcItemInfo, OtcPosition},
-    msgs::{CreateOtcMsg, ExecuteOtcMsg, OtcItemRegistration},
+    definitions::{
+        DailyStats, ExcessFundsPolicy, HookPolicy, OtcBid, OtcItem, OtcItemInfo, OtcPosition,
+        RingTrade,
+    },
+    msgs::{
+        AcceptBidMsg, AcceptUnwindMsg, AddHookMsg, AuctionRegistration, BidAuctionMsg, ClaimOtcMsg,
+        ClaimableItem, ConfigResponse, ContractInfoResponse, CreateOtcMsg, CreateRingTradeMsg,
+        DepositRingLegMsg, ExecuteManyMsg, ExecuteOtcMsg, FeeCollectorRegistration, OtcHookMsg,
+        OtcItemRegistration, PlaceBidMsg, ProposeUnwindMsg, QueryPositionsFilter,
+        RefundRingTradeMsg, RingLegRegistration, SettleAuctionMsg, SolvencyResponse, StatsResponse,
+        UpdateConfigMsg, VestingTimelineResponse, WithdrawBidMsg,
+    },
 };
 use rhaki_cw_plus::{
     math::IntoUint,
@@ -15,7 +25,7 @@ use rhaki_cw_plus::{
 
 use crate::{
     app_ext::{create_code, MergeCoin},
-    cw721_value,
+    cw721_value, mock_hook, mock_oracle,
 };
 
 pub type AppResult = Result<AppResponse, anyhow::Error>;
@@ -35,6 +45,9 @@ pub struct Def<'a> {
     pub fee_collector: &'a str,
     pub owner: &'a str,
     pub otc_fee: Vec<OtcItemInfo>,
+    pub auction_fee: Decimal,
+    pub referral_share: Decimal,
+    pub max_items_per_side: u32,
 }
 
 impl<'a> Def<'a> {
@@ -49,6 +62,9 @@ impl<'a> Def<'a> {
                 denom: "uluna".to_string(),
                 amount: 100_u128.into(),
             }],
+            auction_fee: Decimal::percent(5),
+            referral_share: Decimal::percent(20),
+            max_items_per_side: 5,
         }
     }
 
@@ -66,10 +82,13 @@ impl<'a> Def<'a> {
 pub fn startup(def: &mut Def) -> App {
     let mut app = App::default();
 
-    let otc_code_id = app.store_code(create_code(
-        otcer::contract::instantiate,
-        otcer::contract::execute,
-        otcer::contract::query,
+    let otc_code_id = app.store_code(Box::new(
+        ContractWrapper::new(
+            otcer::contract::execute,
+            otcer::contract::instantiate,
+            otcer::contract::query,
+        )
+        .with_reply(otcer::contract::reply),
     ));
 
     let cw20_code_id = app.store_code(create_code(
@@ -94,7 +113,17 @@ pub fn startup(def: &mut Def) -> App {
             &otcer_pkg::otcer::msgs::InstantiateMsg {
                 owner: def.owner.to_string(),
                 fee: def.otc_fee.clone(),
-                fee_collector: def.fee_collector.to_string(),
+                fee_collectors: vec![FeeCollectorRegistration {
+                    address: def.fee_collector.to_string(),
+                    weight: Decimal::one(),
+                }],
+                auction_fee: def.auction_fee,
+                price_oracle: None,
+                hook_policy: HookPolicy::Revert,
+                referral_share: def.referral_share,
+                excess_funds_policy: ExcessFundsPolicy::Refund,
+                max_items_per_side: def.max_items_per_side,
+                listing_bond: None,
             },
             &[],
             "otc".to_string(),
@@ -107,6 +136,89 @@ pub fn startup(def: &mut Def) -> App {
     app
 }
 
+/// Instantiate a `mock_oracle` and register it as `Config.price_oracle`
+pub fn create_price_oracle(app: &mut App, def: &mut Def) -> Addr {
+    let code_id = app.store_code(create_code(
+        mock_oracle::instantiate,
+        mock_oracle::execute,
+        mock_oracle::query,
+    ));
+
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            def.owner.into_unchecked_addr(),
+            &Empty {},
+            &[],
+            "oracle".to_string(),
+            None,
+        )
+        .unwrap();
+
+    run_update_config(
+        app,
+        def,
+        def.owner,
+        UpdateConfigMsg {
+            owner: None,
+            fee: None,
+            fee_collectors: None,
+            auction_fee: None,
+            price_oracle: Some(addr.to_string()),
+            hook_policy: None,
+            referral_share: None,
+            excess_funds_policy: None,
+            max_items_per_side: None,
+            listing_bond: None,
+        },
+    )
+    .unwrap();
+
+    addr
+}
+
+pub fn create_mock_hook(app: &mut App, def: &Def) -> Addr {
+    let code_id = app.store_code(create_code(
+        mock_hook::instantiate,
+        mock_hook::execute,
+        mock_hook::query,
+    ));
+
+    app.instantiate_contract(
+        code_id,
+        def.owner.into_unchecked_addr(),
+        &Empty {},
+        &[],
+        "hook".to_string(),
+        None,
+    )
+    .unwrap()
+}
+
+pub fn set_mock_hook_fail(app: &mut App, hook: &Addr, fail: bool) {
+    app.execute_contract(
+        hook.clone(),
+        hook.clone(),
+        &mock_hook::ExecuteMsg::SetFail { fail },
+        &[],
+    )
+    .unwrap();
+}
+
+pub fn set_oracle_price(app: &mut App, oracle: &Addr, base: &str, quote: &str, price: Decimal) {
+    app.execute_contract(
+        oracle.clone(),
+        oracle.clone(),
+        &mock_oracle::ExecuteMsg::SetPrice {
+            base: base.to_string(),
+            quote: quote.to_string(),
+            price,
+        },
+        &[],
+    )
+    .unwrap();
+}
+
 fn native_funds_from_otc_item_registration(items: &[OtcItemRegistration]) -> Vec<Coin> {
     items
         .iter()
@@ -125,7 +237,10 @@ fn native_funds_from_otc_item(items: &[OtcItem]) -> Vec<Coin> {
         .iter()
         .filter_map(|item| {
             if let OtcItemInfo::Token { denom, amount } = &item.item_info {
-                Some(Coin::new(amount.u128(), denom))
+                Some(Coin::new(
+                    item.current_amount.unwrap_or(*amount).u128(),
+                    denom,
+                ))
             } else {
                 None
             }
@@ -284,7 +399,7 @@ pub fn run_create_otc(
     app: &mut App,
     def: &mut Def,
     creator: &str,
-    executor: &str,
+    executor: Option<&str>,
     offer: &[OtcItemRegistration],
     ask: &[OtcItemRegistration],
     mut extra_coin: Vec<Coin>,
@@ -299,14 +414,80 @@ pub fn run_create_otc(
         creator.into_unchecked_addr(),
         def.addr_otc.clone().unwrap(),
         &otcer_pkg::otcer::msgs::ExecuteMsg::CreateOtc(CreateOtcMsg {
-            executor: Some(executor.to_string()),
+            executor: executor.map(|val| val.to_string()),
             offer: offer.to_vec(),
             ask: ask.to_vec(),
+            auction: None,
+            hook: None,
+            referrer: None,
+            hash_lock: None,
+            arbiter: None,
+            milestones: None,
         }),
         &coins,
     )
 }
 
+pub fn run_create_auction(
+    app: &mut App,
+    def: &mut Def,
+    creator: &str,
+    offer: &[OtcItemRegistration],
+    auction: AuctionRegistration,
+    mut extra_coin: Vec<Coin>,
+) -> AppResult {
+    let mut coins = native_funds_from_otc_item_registration(offer);
+
+    coins.append(&mut extra_coin);
+
+    let coins = coins.merge();
+
+    app.execute_contract(
+        creator.into_unchecked_addr(),
+        def.addr_otc.clone().unwrap(),
+        &otcer_pkg::otcer::msgs::ExecuteMsg::CreateOtc(CreateOtcMsg {
+            executor: None,
+            offer: offer.to_vec(),
+            ask: vec![],
+            auction: Some(auction),
+            hook: None,
+            referrer: None,
+            hash_lock: None,
+            arbiter: None,
+            milestones: None,
+        }),
+        &coins,
+    )
+}
+
+pub fn run_bid_auction(
+    app: &mut App,
+    def: &mut Def,
+    sender: &str,
+    id: u64,
+    amount: u128,
+    coins: Vec<Coin>,
+) -> AppResult {
+    app.execute_contract(
+        sender.into_unchecked_addr(),
+        def.addr_otc.clone().unwrap(),
+        &otcer_pkg::otcer::msgs::ExecuteMsg::BidAuction(BidAuctionMsg {
+            id,
+            amount: amount.into(),
+        }),
+        &coins,
+    )
+}
+
+pub fn run_settle_auction(app: &mut App, def: &mut Def, sender: &str, id: u64) -> AppResult {
+    app.execute_contract(
+        sender.into_unchecked_addr(),
+        def.addr_otc.clone().unwrap(),
+        &otcer_pkg::otcer::msgs::ExecuteMsg::SettleAuction(SettleAuctionMsg { id }),
+        &[],
+    )
+}
+
 pub fn run_execute_otc(
     app: &mut App,
     def: &mut Def,
@@ -324,11 +505,184 @@ pub fn run_execute_otc(
     app.execute_contract(
         sender.into_unchecked_addr(),
         def.addr_otc.clone().unwrap(),
-        &otcer_pkg::otcer::msgs::ExecuteMsg::ExecuteOtc(ExecuteOtcMsg { id }),
+        &otcer_pkg::otcer::msgs::ExecuteMsg::ExecuteOtc(ExecuteOtcMsg {
+            id,
+            referrer: None,
+            secret: None,
+        }),
+        &coins,
+    )
+}
+
+pub fn run_execute_many(
+    app: &mut App,
+    def: &mut Def,
+    sender: &str,
+    ids: Vec<u64>,
+    coins: Vec<Coin>,
+) -> AppResult {
+    app.execute_contract(
+        sender.into_unchecked_addr(),
+        def.addr_otc.clone().unwrap(),
+        &otcer_pkg::otcer::msgs::ExecuteMsg::ExecuteMany(ExecuteManyMsg {
+            ids,
+            referrer: None,
+        }),
+        &coins.merge(),
+    )
+}
+
+pub fn run_create_ring_trade(
+    app: &mut App,
+    def: &mut Def,
+    sender: &str,
+    legs: Vec<RingLegRegistration>,
+    deadline: u64,
+    coins: Vec<Coin>,
+) -> AppResult {
+    app.execute_contract(
+        sender.into_unchecked_addr(),
+        def.addr_otc.clone().unwrap(),
+        &otcer_pkg::otcer::msgs::ExecuteMsg::CreateRingTrade(CreateRingTradeMsg { legs, deadline }),
         &coins,
     )
 }
 
+pub fn run_deposit_ring_leg(
+    app: &mut App,
+    def: &mut Def,
+    sender: &str,
+    id: u64,
+    coins: Vec<Coin>,
+) -> AppResult {
+    app.execute_contract(
+        sender.into_unchecked_addr(),
+        def.addr_otc.clone().unwrap(),
+        &otcer_pkg::otcer::msgs::ExecuteMsg::DepositRingLeg(DepositRingLegMsg { id }),
+        &coins,
+    )
+}
+
+pub fn run_refund_ring_trade(app: &mut App, def: &mut Def, sender: &str, id: u64) -> AppResult {
+    app.execute_contract(
+        sender.into_unchecked_addr(),
+        def.addr_otc.clone().unwrap(),
+        &otcer_pkg::otcer::msgs::ExecuteMsg::RefundRingTrade(RefundRingTradeMsg { id }),
+        &[],
+    )
+}
+
+pub fn run_place_bid(
+    app: &mut App,
+    def: &mut Def,
+    sender: &str,
+    id: u64,
+    items: &[OtcItemRegistration],
+    mut extra_coin: Vec<Coin>,
+) -> AppResult {
+    let mut coins = native_funds_from_otc_item_registration(items);
+
+    coins.append(&mut extra_coin);
+
+    let coins = coins.merge();
+
+    app.execute_contract(
+        sender.into_unchecked_addr(),
+        def.addr_otc.clone().unwrap(),
+        &otcer_pkg::otcer::msgs::ExecuteMsg::PlaceBid(PlaceBidMsg {
+            id,
+            items: items.to_vec(),
+        }),
+        &coins,
+    )
+}
+
+pub fn run_withdraw_bid(
+    app: &mut App,
+    def: &mut Def,
+    sender: &str,
+    id: u64,
+    bid_id: u64,
+) -> AppResult {
+    app.execute_contract(
+        sender.into_unchecked_addr(),
+        def.addr_otc.clone().unwrap(),
+        &otcer_pkg::otcer::msgs::ExecuteMsg::WithdrawBid(WithdrawBidMsg { id, bid_id }),
+        &[],
+    )
+}
+
+pub fn run_accept_bid(
+    app: &mut App,
+    def: &mut Def,
+    sender: &str,
+    id: u64,
+    bid_id: u64,
+) -> AppResult {
+    app.execute_contract(
+        sender.into_unchecked_addr(),
+        def.addr_otc.clone().unwrap(),
+        &otcer_pkg::otcer::msgs::ExecuteMsg::AcceptBid(AcceptBidMsg { id, bid_id }),
+        &[],
+    )
+}
+
+pub fn run_claim_otc(app: &mut App, def: &mut Def, sender: &str, id: u64) -> AppResult {
+    app.execute_contract(
+        sender.into_unchecked_addr(),
+        def.addr_otc.clone().unwrap(),
+        &otcer_pkg::otcer::msgs::ExecuteMsg::ClaimOtc(ClaimOtcMsg { id }),
+        &[],
+    )
+}
+
+pub fn run_propose_unwind(app: &mut App, def: &mut Def, sender: &str, id: u64) -> AppResult {
+    app.execute_contract(
+        sender.into_unchecked_addr(),
+        def.addr_otc.clone().unwrap(),
+        &otcer_pkg::otcer::msgs::ExecuteMsg::ProposeUnwind(ProposeUnwindMsg { id }),
+        &[],
+    )
+}
+
+pub fn run_accept_unwind(app: &mut App, def: &mut Def, sender: &str, id: u64) -> AppResult {
+    app.execute_contract(
+        sender.into_unchecked_addr(),
+        def.addr_otc.clone().unwrap(),
+        &otcer_pkg::otcer::msgs::ExecuteMsg::AcceptUnwind(AcceptUnwindMsg { id }),
+        &[],
+    )
+}
+
+pub fn run_add_hook(app: &mut App, def: &Def, sender: &str, contract: &Addr) -> AppResult {
+    app.execute_contract(
+        sender.into_unchecked_addr(),
+        def.addr_otc.clone().unwrap(),
+        &otcer_pkg::otcer::msgs::ExecuteMsg::AddHook(AddHookMsg {
+            contract: contract.to_string(),
+        }),
+        &[],
+    )
+}
+
+pub fn run_update_config(
+    app: &mut App,
+    def: &Def,
+    sender: &str,
+    msg: UpdateConfigMsg,
+) -> AppResult {
+    app.execute_contract(
+        sender.into_unchecked_addr(),
+        def.addr_otc.clone().unwrap(),
+        &otcer_pkg::otcer::msgs::ExecuteMsg::UpdateConfig(msg),
+        &[],
+    )
+}
+
+pub fn increase_time(app: &mut App, seconds: u64) {
+    app.update_block(|block| block.time = block.time.plus_seconds(seconds));
+}
+
 // queries
 
 pub fn qy_otc_active_position(app: &App, def: &Def, id: u64) -> StdResult<OtcPosition> {
@@ -338,6 +692,26 @@ pub fn qy_otc_active_position(app: &App, def: &Def, id: u64) -> StdResult<OtcPos
     )
 }
 
+pub fn qy_positions(
+    app: &App,
+    def: &Def,
+    filters: QueryPositionsFilter,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<u64>> {
+    let positions: Vec<OtcPosition> = app.wrap().query_wasm_smart(
+        def.addr_otc.clone().unwrap(),
+        &otcer_pkg::otcer::msgs::QueryMsg::Positions {
+            limit,
+            start_after,
+            filters: Some(filters),
+            order: None,
+        },
+    )?;
+
+    Ok(positions.into_iter().map(|val| val.id).collect())
+}
+
 pub fn qy_otc_executed_position(app: &App, def: &Def, id: u64) -> StdResult<OtcPosition> {
     app.wrap().query_wasm_smart(
         def.addr_otc.clone().unwrap(),
@@ -345,6 +719,107 @@ pub fn qy_otc_executed_position(app: &App, def: &Def, id: u64) -> StdResult<OtcP
     )
 }
 
+pub fn qy_bids(app: &App, def: &Def, id: u64) -> StdResult<Vec<OtcBid>> {
+    app.wrap().query_wasm_smart(
+        def.addr_otc.clone().unwrap(),
+        &otcer_pkg::otcer::msgs::QueryMsg::Bids {
+            id,
+            limit: None,
+            start_after: None,
+        },
+    )
+}
+
+pub fn qy_ring_trade(app: &App, def: &Def, id: u64) -> RingTrade {
+    app.wrap()
+        .query_wasm_smart(
+            def.addr_otc.clone().unwrap(),
+            &otcer_pkg::otcer::msgs::QueryMsg::RingTrade { id },
+        )
+        .unwrap()
+}
+
+pub fn qy_claimable(
+    app: &App,
+    def: &Def,
+    id: u64,
+    address: &str,
+    at_time: Option<u64>,
+) -> StdResult<Vec<ClaimableItem>> {
+    app.wrap().query_wasm_smart(
+        def.addr_otc.clone().unwrap(),
+        &otcer_pkg::otcer::msgs::QueryMsg::Claimable {
+            id,
+            address: address.to_string(),
+            at_time,
+        },
+    )
+}
+
+pub fn qy_vesting_timeline(app: &App, def: &Def, id: u64) -> StdResult<VestingTimelineResponse> {
+    app.wrap().query_wasm_smart(
+        def.addr_otc.clone().unwrap(),
+        &otcer_pkg::otcer::msgs::QueryMsg::VestingTimeline { id },
+    )
+}
+
+pub fn qy_config(app: &App, def: &Def) -> ConfigResponse {
+    app.wrap()
+        .query_wasm_smart(
+            def.addr_otc.clone().unwrap(),
+            &otcer_pkg::otcer::msgs::QueryMsg::Config {},
+        )
+        .unwrap()
+}
+
+pub fn qy_contract_info(app: &App, def: &Def) -> ContractInfoResponse {
+    app.wrap()
+        .query_wasm_smart(
+            def.addr_otc.clone().unwrap(),
+            &otcer_pkg::otcer::msgs::QueryMsg::ContractInfo {},
+        )
+        .unwrap()
+}
+
+pub fn qy_stats(app: &App, def: &Def) -> StatsResponse {
+    app.wrap()
+        .query_wasm_smart(
+            def.addr_otc.clone().unwrap(),
+            &otcer_pkg::otcer::msgs::QueryMsg::Stats {},
+        )
+        .unwrap()
+}
+
+pub fn qy_daily_stats(app: &App, def: &Def, start_after: Option<u64>) -> Vec<DailyStats> {
+    app.wrap()
+        .query_wasm_smart(
+            def.addr_otc.clone().unwrap(),
+            &otcer_pkg::otcer::msgs::QueryMsg::DailyStats {
+                start_after,
+                limit: None,
+            },
+        )
+        .unwrap()
+}
+
+pub fn qy_solvency(app: &App, def: &Def) -> SolvencyResponse {
+    app.wrap()
+        .query_wasm_smart(
+            def.addr_otc.clone().unwrap(),
+            &otcer_pkg::otcer::msgs::QueryMsg::Solvency {
+                limit: None,
+                start_after: None,
+            },
+        )
+        .unwrap()
+}
+
+pub fn qy_mock_hook_received(app: &App, hook: &Addr) -> Vec<OtcHookMsg> {
+    app.wrap()
+        .query_wasm_smart(hook, &mock_hook::QueryMsg::Received {})
+        .unwrap()
+}
+
 pub fn qy_balance_native(app: &App, denom: &str, user: &str) -> Uint128 {
     app.wrap().query_balance(user, denom).unwrap().amount
 }
@@ -0,0 +1,147 @@
+use cosmwasm_std::Coin;
+use cw_multi_test::Executor;
+use otcer_pkg::otcer::{
+    definitions::{HookPolicy, ListingBond, OtcItemInfo},
+    msgs::{
+        AuctionRegistration, CreateOtcMsg, ExecuteMsg, OtcHookMsg, OtcItemRegistration,
+        SlashBondMsg, UpdateConfigMsg,
+    },
+};
+use rhaki_cw_plus::traits::IntoAddr;
+
+use crate::helper::{
+    create_mock_hook, increase_time, mint_token, qy_balance_native, qy_mock_hook_received,
+    run_add_hook, run_bid_auction, run_create_otc, run_execute_otc, run_settle_auction,
+    run_update_config, set_mock_hook_fail, startup, Def, TokenType,
+};
+
+#[test]
+#[rustfmt::skip]
+pub fn test_hooks() {
+    let mut def = Def::new();
+
+    let mut app = startup(&mut def);
+
+    let creator = "creator";
+    let executor = "executor";
+
+    let fee = def.get_native_fee();
+
+    let offer_denom = "luna";
+    let ask_denom = "btc";
+
+    mint_token(&mut app, &mut def, creator, (offer_denom, TokenType::Native), "300");
+    mint_token(&mut app, &mut def, creator, (&fee[0].denom, TokenType::Native), &(fee[0].amount.u128() * 3).to_string());
+    mint_token(&mut app, &mut def, executor, (ask_denom, TokenType::Native), "100");
+    mint_token(&mut app, &mut def, executor, (&fee[0].denom, TokenType::Native), &fee[0].amount.to_string());
+
+    let offer_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: offer_denom.to_string(), amount: 100_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    let ask_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: ask_denom.to_string(), amount: 100_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    let global_hook = create_mock_hook(&mut app, &def);
+    let position_hook = create_mock_hook(&mut app, &def);
+
+    run_add_hook(&mut app, &def, creator, &global_hook).unwrap_err();
+    run_add_hook(&mut app, &def, def.owner, &global_hook).unwrap();
+
+    // per position hook
+
+    let mut coins = offer_items.iter().map(|item| Coin::new(item.item_info.get_amount().u128(), offer_denom)).collect::<Vec<_>>();
+    coins.extend(fee.clone());
+
+    app.execute_contract(
+        creator.into_unchecked_addr(),
+        def.addr_otc.clone().unwrap(),
+        &ExecuteMsg::CreateOtc(CreateOtcMsg { executor: Some(executor.to_string()), offer: offer_items.clone(), ask: ask_items.clone(), auction: None, hook: Some(position_hook.to_string()), referrer: None, hash_lock: None, arbiter: None, milestones: None }),
+        &coins,
+    ).unwrap();
+
+    run_execute_otc(&mut app, &mut def, executor, 1, fee.clone()).unwrap();
+
+    let expected = vec![
+        OtcHookMsg::Created { id: 1, owner: creator.into_unchecked_addr() },
+        OtcHookMsg::Executed { id: 1, owner: creator.into_unchecked_addr(), executor: executor.into_unchecked_addr() },
+    ];
+
+    assert_eq!(expected, qy_mock_hook_received(&app, &global_hook));
+    assert_eq!(expected, qy_mock_hook_received(&app, &position_hook));
+
+    // failing hook reverts the action with HookPolicy::Revert
+
+    set_mock_hook_fail(&mut app, &global_hook, true);
+    run_create_otc(&mut app, &mut def, creator, Some(executor), &offer_items, &ask_items, fee.clone()).unwrap_err();
+
+    // failing hook is ignored with HookPolicy::Ignore
+
+    run_update_config(&mut app, &def, def.owner, UpdateConfigMsg { owner: None, fee: None, fee_collectors: None, auction_fee: None, price_oracle: None, hook_policy: Some(HookPolicy::Ignore), referral_share: None, excess_funds_policy: None, max_items_per_side: None, listing_bond: None }).unwrap();
+    run_create_otc(&mut app, &mut def, creator, Some(executor), &offer_items, &ask_items, fee.clone()).unwrap();
+
+    assert_eq!(2, qy_mock_hook_received(&app, &global_hook).len());
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_failing_position_hook() {
+    let mut def = Def::new();
+
+    let mut app = startup(&mut def);
+
+    let creator = "creator";
+    let bidder = "bidder";
+    let otc = def.addr_otc.clone().unwrap();
+
+    let fee = def.get_native_fee();
+
+    mint_token(&mut app, &mut def, creator, ("luna", TokenType::Native), "200");
+    mint_token(&mut app, &mut def, creator, ("bond", TokenType::Native), "10");
+    mint_token(&mut app, &mut def, creator, (&fee[0].denom, TokenType::Native), &(fee[0].amount.u128() * 2).to_string());
+    mint_token(&mut app, &mut def, bidder, ("btc", TokenType::Native), "100");
+
+    let position_hook = create_mock_hook(&mut app, &def);
+    set_mock_hook_fail(&mut app, &position_hook, true);
+
+    let offer_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: "luna".to_string(), amount: 100_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    let create_msg = |auction: Option<AuctionRegistration>, ask: Vec<OtcItemRegistration>| ExecuteMsg::CreateOtc(CreateOtcMsg { executor: None, offer: offer_items.clone(), ask, auction, hook: Some(position_hook.to_string()), referrer: None, hash_lock: None, arbiter: None, milestones: None });
+
+    let mut coins = vec![Coin::new(100, "luna")];
+    coins.extend(fee.clone());
+
+    // the owner hook can't lock the bids of an auction
+
+    let auction = AuctionRegistration { reserve: OtcItemInfo::Token { denom: "btc".to_string(), amount: 100_u128.into() }, min_increment: 1_u128.into(), end_time: app.block_info().time.seconds() + 100, extension: 0 };
+
+    app.execute_contract(creator.into_unchecked_addr(), otc.clone(), &create_msg(Some(auction), vec![]), &coins).unwrap();
+    run_bid_auction(&mut app, &mut def, bidder, 1, 100, vec![Coin::new(100, "btc")]).unwrap();
+
+    increase_time(&mut app, 100);
+    run_settle_auction(&mut app, &mut def, bidder, 1).unwrap();
+
+    assert_eq!(100, qy_balance_native(&app, "luna", bidder).u128());
+
+    // nor the slash of an expired listing bond
+
+    run_update_config(&mut app, &def, def.owner, UpdateConfigMsg { owner: None, fee: None, fee_collectors: None, auction_fee: None, price_oracle: None, hook_policy: None, referral_share: None, excess_funds_policy: None, max_items_per_side: None, listing_bond: Some(ListingBond { asset: OtcItemInfo::Token { denom: "bond".to_string(), amount: 10_u128.into() }, duration: 100 }) }).unwrap();
+
+    coins.push(Coin::new(10, "bond"));
+
+    let ask_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: "btc".to_string(), amount: 50_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    app.execute_contract(creator.into_unchecked_addr(), otc.clone(), &create_msg(None, ask_items), &coins).unwrap();
+
+    increase_time(&mut app, 100);
+    app.execute_contract(bidder.into_unchecked_addr(), otc.clone(), &ExecuteMsg::SlashBond(SlashBondMsg { id: 2 }), &[]).unwrap();
+
+    assert_eq!(10, qy_balance_native(&app, "bond", def.fee_collector).u128());
+    assert_eq!(100, qy_balance_native(&app, "luna", creator).u128());
+    assert!(qy_mock_hook_received(&app, &position_hook).is_empty());
+}
@@ -1,9 +1,12 @@
-use otcer_pkg::otcer::{definitions::OtcItemInfo, msgs::OtcItemRegistration};
+use otcer_pkg::otcer::{
+    definitions::{OtcItemInfo, OtcPositionStatus},
+    msgs::OtcItemRegistration,
+};
 
 use crate::helper::{
     create_token, increase_allowance, mint_token, qy_balance_cw20, qy_balance_native,
-    qy_balance_nft, qy_otc_active_position, qy_otc_executed_position, run_create_otc,
-    run_execute_otc, startup, Def, TokenType,
+    qy_balance_nft, qy_otc_executed_position, run_create_otc, run_execute_otc, startup, Def,
+    TokenType,
 };
 
 #[test]
@@ -46,22 +49,22 @@ pub fn test() {
     // Create otc
 
     let offer_items = vec![
-        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: offer_native_denom.to_string(), amount: offer_native_amount.into() }, vesting: None },
-        OtcItemRegistration { item_info: OtcItemInfo::Cw20 { contract: offer_cw20_addr.clone(), amount: offer_cw20_amount.into() }, vesting: None },
-        OtcItemRegistration { item_info: OtcItemInfo::Cw721 { contract: offer_nft_addr.clone(), token_id: offer_nft_id.to_string() }, vesting: None }
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: offer_native_denom.to_string(), amount: offer_native_amount.into() }, vesting: None, pricing: None },
+        OtcItemRegistration { item_info: OtcItemInfo::Cw20 { contract: offer_cw20_addr.clone(), amount: offer_cw20_amount.into() }, vesting: None, pricing: None },
+        OtcItemRegistration { item_info: OtcItemInfo::Cw721 { contract: offer_nft_addr.clone(), token_id: offer_nft_id.to_string() }, vesting: None, pricing: None }
     ];
 
     let ask_items = vec![
-        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: ask_native_denom.to_string(), amount: ask_native_amount.into() }, vesting: None },
-        OtcItemRegistration { item_info: OtcItemInfo::Cw20 { contract: ask_cw20_addr.clone(), amount: ask_cw20_amount.into() }, vesting: None },
-        OtcItemRegistration { item_info: OtcItemInfo::Cw721 { contract: ask_nft_addr.clone(), token_id: ask_nft_id.to_string() }, vesting: None }
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: ask_native_denom.to_string(), amount: ask_native_amount.into() }, vesting: None, pricing: None },
+        OtcItemRegistration { item_info: OtcItemInfo::Cw20 { contract: ask_cw20_addr.clone(), amount: ask_cw20_amount.into() }, vesting: None, pricing: None },
+        OtcItemRegistration { item_info: OtcItemInfo::Cw721 { contract: ask_nft_addr.clone(), token_id: ask_nft_id.to_string() }, vesting: None, pricing: None }
     ];
 
     // fails for missing fee
 
-    run_create_otc(&mut app, &mut def, creator, executor, &offer_items, &ask_items, vec![]).unwrap_err();
+    run_create_otc(&mut app, &mut def, creator, Some(executor), &offer_items, &ask_items, vec![]).unwrap_err();
     mint_token(&mut app, &mut def, creator, (&fee[0].denom, TokenType::Native), &fee[0].amount.to_string());
-    run_create_otc(&mut app, &mut def, creator, executor, &offer_items, &ask_items, fee.clone()).unwrap();
+    run_create_otc(&mut app, &mut def, creator, Some(executor), &offer_items, &ask_items, fee.clone()).unwrap();
 
     // assert position
 
@@ -90,7 +93,7 @@ pub fn test() {
 
     assert_eq!(fee[0].amount.u128() * 2, qy_balance_native(&app, &fee[0].denom, def.fee_collector).u128());
 
-    qy_otc_executed_position(&app, &def, 1).unwrap();
-    qy_otc_active_position(&app, &def, 1).unwrap_err();
+    let position = qy_otc_executed_position(&app, &def, 1).unwrap();
+    assert!(matches!(position.status, OtcPositionStatus::Executed(..)));
 
 }
@@ -2,8 +2,54 @@
 #[cfg(test)]
 mod app_ext;
 #[cfg(test)]
+mod asset_index_test;
+#[cfg(test)]
+mod auction_test;
+#[cfg(test)]
+mod bids_test;
+#[cfg(test)]
+mod claimable_test;
+#[cfg(test)]
+mod config_test;
+#[cfg(test)]
 mod cw721_value;
 #[cfg(test)]
+mod dispute_test;
+#[cfg(test)]
+mod execute_many_test;
+#[cfg(test)]
+mod fee_test;
+#[cfg(test)]
+mod hash_lock_test;
+#[cfg(test)]
 mod helper;
 #[cfg(test)]
+mod hooks_test;
+#[cfg(test)]
 mod integration_test;
+#[cfg(test)]
+mod listing_bond_test;
+#[cfg(test)]
+mod migrate_test;
+#[cfg(test)]
+mod milestone_test;
+#[cfg(test)]
+mod mock_hook;
+#[cfg(test)]
+mod mock_oracle;
+#[cfg(test)]
+mod pricing_test;
+#[cfg(test)]
+mod ring_trade_test;
+#[cfg(test)]
+mod signed_order_test;
+#[cfg(test)]
+mod solvency_test;
+#[cfg(test)]
+mod stats_test;
+#[cfg(test)]
+mod unwind_test;
+#[cfg(test)]
+mod validation_test;
+#[cfg(test)]
+mod vesting_timeline_test;
@@ -0,0 +1,111 @@
+use cosmwasm_std::Coin;
+use cw_multi_test::Executor;
+use otcer_pkg::otcer::{
+    definitions::{AssetAmount, BondStatus, ListingBond, OtcItemInfo},
+    msgs::{CancelOtcMsg, ExecuteMsg, OtcItemRegistration, SlashBondMsg, UpdateConfigMsg},
+};
+use rhaki_cw_plus::traits::IntoAddr;
+
+use crate::helper::{
+    increase_time, mint_token, qy_balance_native, qy_otc_active_position, qy_solvency, qy_stats,
+    run_create_otc, run_execute_otc, run_update_config, startup, Def, TokenType,
+};
+
+#[test]
+#[rustfmt::skip]
+pub fn test_listing_bond() {
+    let mut def = Def::new();
+
+    let mut app = startup(&mut def);
+
+    let creator = "creator";
+    let executor = "executor";
+    let otc = def.addr_otc.clone().unwrap();
+
+    let fee = def.get_native_fee();
+
+    mint_token(&mut app, &mut def, creator, ("luna", TokenType::Native), "500");
+    mint_token(&mut app, &mut def, creator, ("bond", TokenType::Native), "50");
+    mint_token(&mut app, &mut def, creator, (&fee[0].denom, TokenType::Native), &(fee[0].amount.u128() * 5).to_string());
+    mint_token(&mut app, &mut def, executor, ("btc", TokenType::Native), "50");
+    mint_token(&mut app, &mut def, executor, (&fee[0].denom, TokenType::Native), &fee[0].amount.to_string());
+
+    let update_msg = |asset: OtcItemInfo, duration: u64| UpdateConfigMsg {
+        owner: None,
+        fee: None,
+        fee_collectors: None,
+        auction_fee: None,
+        price_oracle: None,
+        hook_policy: None,
+        referral_share: None,
+        excess_funds_policy: None,
+        max_items_per_side: None,
+        listing_bond: Some(ListingBond { asset, duration }),
+    };
+
+    let bond = OtcItemInfo::Token { denom: "bond".to_string(), amount: 10_u128.into() };
+
+    run_update_config(&mut app, &def, def.owner, update_msg(bond.clone(), 0)).unwrap_err();
+    run_update_config(&mut app, &def, def.owner, update_msg(OtcItemInfo::Cw721 { contract: otc.clone(), token_id: "1".to_string() }, 100)).unwrap_err();
+    run_update_config(&mut app, &def, def.owner, update_msg(bond.clone(), 100)).unwrap();
+
+    let offer_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: "luna".to_string(), amount: 100_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    let ask_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: "btc".to_string(), amount: 50_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    let mut coins = fee.clone();
+    coins.push(Coin::new(10, "bond"));
+
+    // the bond is required and returned on execution
+
+    run_create_otc(&mut app, &mut def, creator, Some(executor), &offer_items, &ask_items, fee.clone()).unwrap_err();
+    run_create_otc(&mut app, &mut def, creator, Some(executor), &offer_items, &ask_items, coins.clone()).unwrap();
+
+    assert_eq!(BondStatus::Locked, qy_otc_active_position(&app, &def, 1).unwrap().bond.unwrap().status);
+    assert_eq!(40, qy_balance_native(&app, "bond", creator).u128());
+
+    run_execute_otc(&mut app, &mut def, executor, 1, fee.clone()).unwrap();
+
+    assert_eq!(BondStatus::Returned, qy_otc_active_position(&app, &def, 1).unwrap().bond.unwrap().status);
+    assert_eq!(50, qy_balance_native(&app, "bond", creator).u128());
+
+    // returned on cancel before the expiry
+
+    run_create_otc(&mut app, &mut def, creator, Some(executor), &offer_items, &ask_items, coins.clone()).unwrap();
+    app.execute_contract(creator.into_unchecked_addr(), otc.clone(), &ExecuteMsg::CancelOtc(CancelOtcMsg { id: 2 }), &[]).unwrap();
+
+    assert_eq!(50, qy_balance_native(&app, "bond", creator).u128());
+
+    // slashed if the position expires untouched
+
+    run_create_otc(&mut app, &mut def, creator, Some(executor), &offer_items, &ask_items, coins.clone()).unwrap();
+    run_create_otc(&mut app, &mut def, creator, Some(executor), &offer_items, &ask_items, coins.clone()).unwrap();
+
+    app.execute_contract("stranger".into_unchecked_addr(), otc.clone(), &ExecuteMsg::SlashBond(SlashBondMsg { id: 3 }), &[]).unwrap_err();
+
+    increase_time(&mut app, 100);
+
+    run_execute_otc(&mut app, &mut def, executor, 3, fee.clone()).unwrap_err();
+
+    app.execute_contract("stranger".into_unchecked_addr(), otc.clone(), &ExecuteMsg::SlashBond(SlashBondMsg { id: 3 }), &[]).unwrap();
+    app.execute_contract(creator.into_unchecked_addr(), otc.clone(), &ExecuteMsg::CancelOtc(CancelOtcMsg { id: 4 }), &[]).unwrap();
+
+    assert_eq!(20, qy_balance_native(&app, "bond", def.fee_collector).u128());
+    assert!(qy_stats(&app, &def).fees.contains(&AssetAmount { asset: "bond".to_string(), amount: 20_u128.into() }));
+    assert_eq!(30, qy_balance_native(&app, "bond", creator).u128());
+    assert_eq!(400, qy_balance_native(&app, "luna", creator).u128());
+
+    qy_otc_active_position(&app, &def, 3).unwrap_err();
+
+    // a zero amount removes the bond
+
+    run_update_config(&mut app, &def, def.owner, update_msg(OtcItemInfo::Token { denom: "bond".to_string(), amount: 0_u128.into() }, 100)).unwrap();
+    run_create_otc(&mut app, &mut def, creator, Some(executor), &offer_items, &ask_items, fee.clone()).unwrap();
+
+    assert!(qy_otc_active_position(&app, &def, 5).unwrap().bond.is_none());
+    assert!(qy_solvency(&app, &def).solvent);
+}
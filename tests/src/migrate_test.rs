@@ -0,0 +1,158 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    from_json,
+    testing::{mock_dependencies, mock_env, mock_info},
+    to_json_vec, Addr, Coin, Decimal, Deps, Storage, Uint128,
+};
+use cw_storage_plus::Map;
+use otcer::contract::{execute, migrate, query};
+use otcer_pkg::otcer::{
+    definitions::{
+        ExcessFundsPolicy, FeeCollector, HookPolicy, OtcItemInfo, OtcPosition, OtcPositionStatus,
+    },
+    msgs::{
+        CancelOtcMsg, ConfigResponse, ExecuteMsg, MigrateMsg, QueryMsg, QueryPositionsFilter,
+        SolvencyResponse, StatsResponse, StatusCount,
+    },
+};
+use rhaki_cw_plus::traits::IntoAddr;
+
+/// `Config` saved before the weighted fee collectors
+#[cw_serde]
+struct LegacyConfig {
+    owner: String,
+    counter_otc: u64,
+    fee: Vec<OtcItemInfo>,
+    fee_collector: String,
+}
+
+/// `OtcPosition` saved before the escrow ledger and the asset indexes
+#[cw_serde]
+struct LegacyPosition {
+    id: u64,
+    owner: Addr,
+    executor: Option<Addr>,
+    offer: Vec<LegacyItem>,
+    ask: Vec<LegacyItem>,
+    creation_time: u64,
+    status: OtcPositionStatus,
+}
+
+#[cw_serde]
+struct LegacyItem {
+    item_info: OtcItemInfo,
+    vesting_info: Option<LegacyVestingInfo>,
+}
+
+#[cw_serde]
+struct LegacyVestingInfo {
+    cliff: Option<u64>,
+    vesting: Option<u64>,
+    claimed: Uint128,
+}
+
+const LEGACY_POSITIONS: Map<u64, LegacyPosition> = Map::new("active_position");
+
+#[test]
+#[rustfmt::skip]
+pub fn test_migrate_legacy_config() {
+    let mut deps = mock_dependencies();
+
+    let fee = vec![OtcItemInfo::Token { denom: "uluna".to_string(), amount: Uint128::new(100) }];
+
+    let legacy = LegacyConfig { owner: "owner".to_string(), counter_otc: 7, fee: fee.clone(), fee_collector: "collector".to_string() };
+    deps.storage.set(b"config", &to_json_vec(&legacy).unwrap());
+
+    // the single fee collector gets the whole fee, new features are disabled
+
+    migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+    let config: ConfigResponse = from_json(query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+
+    assert_eq!("owner", config.owner.as_str());
+    assert_eq!((7, 0, 0), (config.counter_otc, config.counter_bid, config.counter_ring));
+    assert_eq!(fee, config.fee);
+    assert_eq!(vec![FeeCollector { address: "collector".into_unchecked_addr(), weight: Decimal::one() }], config.fee_collectors);
+    assert_eq!((Decimal::zero(), Decimal::zero()), (config.auction_fee, config.referral_share));
+    assert_eq!((HookPolicy::Revert, ExcessFundsPolicy::Refund), (config.hook_policy.clone(), config.excess_funds_policy.clone()));
+    assert!(config.price_oracle.is_none() && config.listing_bond.is_none());
+
+    // a config already migrated is kept
+
+    migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+    let migrated: ConfigResponse = from_json(query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(config, migrated);
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_migrate_legacy_positions() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let now = env.block.time.seconds();
+
+    let legacy = LegacyConfig { owner: "owner".to_string(), counter_otc: 2, fee: vec![], fee_collector: "collector".to_string() };
+    deps.storage.set(b"config", &to_json_vec(&legacy).unwrap());
+
+    let luna = |amount: u128| OtcItemInfo::Token { denom: "luna".to_string(), amount: Uint128::new(amount) };
+    let btc = |amount: u128| OtcItemInfo::Token { denom: "btc".to_string(), amount: Uint128::new(amount) };
+
+    let pending = LegacyPosition {
+        id: 1,
+        owner: "creator".into_unchecked_addr(),
+        executor: None,
+        offer: vec![LegacyItem { item_info: luna(100), vesting_info: None }],
+        ask: vec![LegacyItem { item_info: btc(50), vesting_info: None }],
+        creation_time: now,
+        status: OtcPositionStatus::Pending,
+    };
+
+    let vesting = LegacyPosition {
+        id: 2,
+        owner: "creator".into_unchecked_addr(),
+        executor: Some("executor".into_unchecked_addr()),
+        offer: vec![LegacyItem { item_info: luna(100), vesting_info: Some(LegacyVestingInfo { cliff: None, vesting: Some(1_000), claimed: Uint128::new(40) }) }],
+        ask: vec![LegacyItem { item_info: btc(50), vesting_info: None }],
+        creation_time: now,
+        status: OtcPositionStatus::Vesting(now),
+    };
+
+    LEGACY_POSITIONS.save(&mut deps.storage, 1, &pending).unwrap();
+    LEGACY_POSITIONS.save(&mut deps.storage, 2, &vesting).unwrap();
+    deps.querier.update_balance(env.contract.address.clone(), vec![Coin::new(160, "luna")]);
+
+    // the pending offer and the unclaimed vesting are escrowed
+
+    migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+
+    let solvency = |deps: Deps| -> SolvencyResponse { from_json(query(deps, mock_env(), QueryMsg::Solvency { limit: None, start_after: None }).unwrap()).unwrap() };
+
+    let response = solvency(deps.as_ref());
+    assert!(response.solvent);
+    assert_eq!(vec![luna(160)], response.assets.iter().map(|val| val.liability.clone()).collect::<Vec<_>>());
+
+    // the legacy positions are indexed and counted
+
+    let count = |status: &str, count: u64| StatusCount { status: status.to_string(), count };
+    let stats = |deps: Deps| -> StatsResponse { from_json(query(deps, mock_env(), QueryMsg::Stats {}).unwrap()).unwrap() };
+
+    let by_asset = |deps: Deps, offer_asset: Option<&str>, ask_asset: Option<&str>| -> Vec<u64> {
+        let filters = QueryPositionsFilter { owner: None, executor: None, status: None, offer_asset: offer_asset.map(|val| val.to_string()), ask_asset: ask_asset.map(|val| val.to_string()) };
+        let positions: Vec<OtcPosition> = from_json(query(deps, mock_env(), QueryMsg::Positions { limit: None, start_after: None, filters: Some(filters), order: None }).unwrap()).unwrap();
+        positions.into_iter().map(|val| val.id).collect()
+    };
+
+    assert_eq!(vec![count("pending", 1), count("vesting", 1)], stats(deps.as_ref()).positions);
+    assert_eq!(vec![2, 1], by_asset(deps.as_ref(), Some("luna"), None));
+    assert_eq!(vec![2, 1], by_asset(deps.as_ref(), Some("luna"), Some("btc")));
+    assert!(by_asset(deps.as_ref(), Some("btc"), None).is_empty());
+
+    // the legacy positions can be closed
+
+    execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), ExecuteMsg::CancelOtc(CancelOtcMsg { id: 1 })).unwrap();
+
+    assert_eq!(vec![luna(60)], solvency(deps.as_ref()).assets.iter().map(|val| val.liability.clone()).collect::<Vec<_>>());
+    assert_eq!(vec![count("pending", 0), count("vesting", 1)], stats(deps.as_ref()).positions);
+    assert_eq!(vec![2], by_asset(deps.as_ref(), Some("luna"), None));
+}
@@ -0,0 +1,121 @@
+use cosmwasm_std::{Coin, Decimal};
+use cw_multi_test::Executor;
+use otcer_pkg::otcer::{
+    definitions::{OtcItemInfo, OtcPositionStatus, Tranche},
+    msgs::{
+        ApproveMilestoneMsg, CreateOtcMsg, ExecuteMsg, MilestonePlanRegistration,
+        OtcItemRegistration, VestingInfoRegistration,
+    },
+};
+use rhaki_cw_plus::traits::IntoAddr;
+
+use crate::helper::{
+    increase_time, mint_token, qy_balance_native, qy_otc_active_position, run_claim_otc,
+    run_execute_otc, startup, Def, TokenType,
+};
+
+#[test]
+#[rustfmt::skip]
+pub fn test_milestones() {
+    let mut def = Def::new();
+
+    let mut app = startup(&mut def);
+
+    let creator = "creator";
+    let executor = "executor";
+    let approver = "approver";
+    let otc = def.addr_otc.clone().unwrap();
+
+    let fee = def.get_native_fee();
+
+    mint_token(&mut app, &mut def, creator, ("luna", TokenType::Native), "1500");
+    mint_token(&mut app, &mut def, creator, (&fee[0].denom, TokenType::Native), &(fee[0].amount.u128() * 2).to_string());
+    mint_token(&mut app, &mut def, executor, ("btc", TokenType::Native), "200");
+    mint_token(&mut app, &mut def, executor, (&fee[0].denom, TokenType::Native), &(fee[0].amount.u128() * 2).to_string());
+
+    let tranches = |shares: Vec<(u32, u64)>| Some(shares.into_iter().map(|(milestone, share)| Tranche { milestone, share: Decimal::percent(share) }).collect());
+
+    let item = |denom: &str, amount: u128, vesting: Option<VestingInfoRegistration>| OtcItemRegistration { item_info: OtcItemInfo::Token { denom: denom.to_string(), amount: amount.into() }, vesting, pricing: None };
+
+    let create_msg = |offer: Vec<OtcItemRegistration>, ask: Vec<OtcItemRegistration>, approver: Option<&str>| ExecuteMsg::CreateOtc(CreateOtcMsg {
+        executor: Some(executor.to_string()),
+        offer,
+        ask,
+        auction: None,
+        hook: None,
+        referrer: None,
+        hash_lock: None,
+        arbiter: None,
+        milestones: Some(MilestonePlanRegistration { approver: approver.map(|val| val.to_string()), milestones: vec!["mainnet".to_string(), "listing".to_string()] }),
+    });
+
+    let approve_msg = |id: u64, milestone: u32| ExecuteMsg::ApproveMilestone(ApproveMilestoneMsg { id, milestone });
+
+    let mut coins = vec![Coin::new(1_000, "luna")];
+    coins.extend(fee.clone());
+
+    // tranches must reference defined milestones and sum to 1, the approver can't be a party
+
+    let offer = |tranches: Option<Vec<Tranche>>| vec![item("luna", 1_000, Some(VestingInfoRegistration { cliff: None, vesting: Some(1_000), tranches }))];
+    let ask = vec![item("btc", 100, None)];
+
+    app.execute_contract(creator.into_unchecked_addr(), otc.clone(), &create_msg(offer(tranches(vec![(0, 30), (2, 70)])), ask.clone(), None), &coins).unwrap_err();
+    app.execute_contract(creator.into_unchecked_addr(), otc.clone(), &create_msg(offer(tranches(vec![(0, 30), (1, 60)])), ask.clone(), None), &coins).unwrap_err();
+    app.execute_contract(creator.into_unchecked_addr(), otc.clone(), &create_msg(offer(tranches(vec![(0, 30), (1, 70)])), ask.clone(), Some(executor)), &coins).unwrap_err();
+    app.execute_contract(creator.into_unchecked_addr(), otc.clone(), &create_msg(offer(tranches(vec![(0, 30), (1, 70)])), ask.clone(), None), &coins).unwrap();
+
+    run_execute_otc(&mut app, &mut def, executor, 1, fee.clone()).unwrap();
+
+    // nothing is unlocked before the milestones
+
+    increase_time(&mut app, 500);
+    run_claim_otc(&mut app, &mut def, executor, 1).unwrap_err();
+
+    // without an approver both parties must approve
+
+    app.execute_contract(approver.into_unchecked_addr(), otc.clone(), &approve_msg(1, 0), &[]).unwrap_err();
+    app.execute_contract(creator.into_unchecked_addr(), otc.clone(), &approve_msg(1, 0), &[]).unwrap();
+    app.execute_contract(creator.into_unchecked_addr(), otc.clone(), &approve_msg(1, 0), &[]).unwrap_err();
+    run_claim_otc(&mut app, &mut def, executor, 1).unwrap_err();
+
+    app.execute_contract(executor.into_unchecked_addr(), otc.clone(), &approve_msg(1, 0), &[]).unwrap();
+    app.execute_contract(executor.into_unchecked_addr(), otc.clone(), &approve_msg(1, 2), &[]).unwrap_err();
+
+    // 1000 * 30% * 50% of the vesting
+
+    run_claim_otc(&mut app, &mut def, executor, 1).unwrap();
+    assert_eq!(150, qy_balance_native(&app, "luna", executor).u128());
+
+    increase_time(&mut app, 500);
+    run_claim_otc(&mut app, &mut def, executor, 1).unwrap();
+    assert_eq!(300, qy_balance_native(&app, "luna", executor).u128());
+
+    app.execute_contract(creator.into_unchecked_addr(), otc.clone(), &approve_msg(1, 1), &[]).unwrap();
+    app.execute_contract(executor.into_unchecked_addr(), otc.clone(), &approve_msg(1, 1), &[]).unwrap();
+
+    run_claim_otc(&mut app, &mut def, executor, 1).unwrap();
+    assert_eq!(1_000, qy_balance_native(&app, "luna", executor).u128());
+    assert!(matches!(qy_otc_active_position(&app, &def, 1).unwrap().status, OtcPositionStatus::Executed(..)));
+
+    // the approver completes the milestones alone, tranches without time conditions
+
+    app.execute_contract(executor.into_unchecked_addr(), otc.clone(), &ExecuteMsg::CreateOtc(CreateOtcMsg {
+        executor: Some(creator.to_string()),
+        offer: vec![item("btc", 100, Some(VestingInfoRegistration { cliff: None, vesting: None, tranches: tranches(vec![(0, 40), (1, 60)]) }))],
+        ask: vec![item("luna", 500, None)],
+        auction: None,
+        hook: None,
+        referrer: None,
+        hash_lock: None,
+        arbiter: None,
+        milestones: Some(MilestonePlanRegistration { approver: Some(approver.to_string()), milestones: vec!["mainnet".to_string(), "listing".to_string()] }),
+    }), &[Coin::new(100, "btc"), fee[0].clone()]).unwrap();
+
+    run_execute_otc(&mut app, &mut def, creator, 2, fee.clone()).unwrap();
+
+    app.execute_contract(creator.into_unchecked_addr(), otc.clone(), &approve_msg(2, 0), &[]).unwrap_err();
+    app.execute_contract(approver.into_unchecked_addr(), otc.clone(), &approve_msg(2, 0), &[]).unwrap();
+
+    run_claim_otc(&mut app, &mut def, creator, 2).unwrap();
+    assert_eq!(140, qy_balance_native(&app, "btc", creator).u128());
+}
@@ -0,0 +1,64 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    entry_point, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError, StdResult,
+};
+use cw_storage_plus::Item;
+use otcer_pkg::otcer::msgs::OtcHookMsg;
+use rhaki_cw_plus::traits::IntoBinary;
+
+const RECEIVED: Item<Vec<OtcHookMsg>> = Item::new("received");
+const FAIL: Item<bool> = Item::new("fail");
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    OtcHook(OtcHookMsg),
+    SetFail { fail: bool },
+}
+
+#[cw_serde]
+pub enum QueryMsg {
+    Received {},
+}
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: Empty,
+) -> StdResult<Response> {
+    RECEIVED.save(deps.storage, &vec![])?;
+    FAIL.save(deps.storage, &false)?;
+    Ok(Response::new())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: ExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::OtcHook(hook_msg) => {
+            if FAIL.load(deps.storage)? {
+                return Err(StdError::generic_err("Hook failed"));
+            }
+
+            RECEIVED.update(deps.storage, |mut received| -> StdResult<_> {
+                received.push(hook_msg);
+                Ok(received)
+            })?;
+        }
+        ExecuteMsg::SetFail { fail } => FAIL.save(deps.storage, &fail)?,
+    }
+
+    Ok(Response::new())
+}
+
+#[entry_point]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Received {} => RECEIVED.load(deps.storage)?.into_binary(),
+    }
+}
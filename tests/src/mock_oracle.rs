@@ -0,0 +1,59 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    entry_point, Binary, Decimal, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult,
+};
+use cw_storage_plus::Map;
+use otcer_pkg::otcer::oracle::{OracleQueryMsg, PriceResponse};
+use rhaki_cw_plus::traits::IntoBinary;
+
+const PRICES: Map<(String, String), PriceResponse> = Map::new("prices");
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    SetPrice {
+        base: String,
+        quote: String,
+        price: Decimal,
+    },
+}
+
+#[entry_point]
+pub fn instantiate(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: Empty,
+) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    msg: ExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::SetPrice { base, quote, price } => {
+            PRICES.save(
+                deps.storage,
+                (base, quote),
+                &PriceResponse {
+                    price,
+                    last_updated: env.block.time.seconds(),
+                },
+            )?;
+            Ok(Response::new())
+        }
+    }
+}
+
+#[entry_point]
+pub fn query(deps: Deps, _env: Env, msg: OracleQueryMsg) -> StdResult<Binary> {
+    match msg {
+        OracleQueryMsg::Price { base, quote } => {
+            PRICES.load(deps.storage, (base, quote))?.into_binary()
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use cosmwasm_std::Decimal;
+use otcer_pkg::otcer::{
+    definitions::{DutchPricing, ItemPricing, OraclePricing, OtcItemInfo, PriceAdjustment},
+    msgs::OtcItemRegistration,
+};
+
+use crate::helper::{
+    create_price_oracle, increase_time, mint_token, qy_balance_native, qy_otc_active_position,
+    run_create_otc, run_execute_otc, set_oracle_price, startup, Def, TokenType,
+};
+
+#[test]
+#[rustfmt::skip]
+pub fn test_dutch_pricing() {
+    let mut def = Def::new();
+
+    let mut app = startup(&mut def);
+
+    let creator = "creator";
+    let executor = "executor";
+
+    let fee = def.get_native_fee();
+
+    let offer_denom = "luna";
+    let ask_denom = "btc";
+
+    mint_token(&mut app, &mut def, creator, (offer_denom, TokenType::Native), "200");
+    mint_token(&mut app, &mut def, creator, (&fee[0].denom, TokenType::Native), &(fee[0].amount.u128() * 2).to_string());
+    mint_token(&mut app, &mut def, executor, (ask_denom, TokenType::Native), "1000");
+    mint_token(&mut app, &mut def, executor, (&fee[0].denom, TokenType::Native), &fee[0].amount.to_string());
+
+    let now = app.block_info().time.seconds();
+
+    let offer_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: offer_denom.to_string(), amount: 100_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    let linear_ask = vec![
+        OtcItemRegistration {
+            item_info: OtcItemInfo::Token { denom: ask_denom.to_string(), amount: 1000_u128.into() },
+            vesting: None,
+            pricing: Some(ItemPricing::Dutch(DutchPricing { floor: 500_u128.into(), start_time: now + 100, end_time: now + 1100, steps: None })),
+        },
+    ];
+
+    let step_ask = vec![
+        OtcItemRegistration {
+            item_info: OtcItemInfo::Token { denom: ask_denom.to_string(), amount: 1000_u128.into() },
+            vesting: None,
+            pricing: Some(ItemPricing::Dutch(DutchPricing { floor: 500_u128.into(), start_time: now + 100, end_time: now + 1100, steps: Some(4) })),
+        },
+    ];
+
+    // pricing is not allowed on offer items
+
+    run_create_otc(&mut app, &mut def, creator, Some(executor), &linear_ask, &offer_items, fee.clone()).unwrap_err();
+
+    run_create_otc(&mut app, &mut def, creator, Some(executor), &offer_items, &linear_ask, fee.clone()).unwrap();
+    run_create_otc(&mut app, &mut def, creator, Some(executor), &offer_items, &step_ask, fee.clone()).unwrap();
+
+    assert_eq!(1000, qy_otc_active_position(&app, &def, 1).unwrap().ask[0].current_amount.unwrap().u128());
+
+    increase_time(&mut app, 700);
+
+    // the registered amount is left untouched
+    assert_eq!(1000, qy_otc_active_position(&app, &def, 1).unwrap().ask[0].item_info.get_amount().u128());
+
+    assert_eq!(700, qy_otc_active_position(&app, &def, 1).unwrap().ask[0].current_amount.unwrap().u128());
+    assert_eq!(750, qy_otc_active_position(&app, &def, 2).unwrap().ask[0].current_amount.unwrap().u128());
+
+    // the exact current amount is collected
+
+    run_execute_otc(&mut app, &mut def, executor, 1, fee.clone()).unwrap();
+
+    assert_eq!(700, qy_balance_native(&app, ask_denom, creator).u128());
+    assert_eq!(300, qy_balance_native(&app, ask_denom, executor).u128());
+    assert_eq!(700, qy_otc_active_position(&app, &def, 1).unwrap().ask[0].item_info.get_amount().u128());
+
+    // after end time the price stays at the floor
+
+    increase_time(&mut app, 10_000);
+
+    assert_eq!(500, qy_otc_active_position(&app, &def, 2).unwrap().ask[0].current_amount.unwrap().u128());
+    assert_eq!(0, qy_balance_native(&app, ask_denom, def.addr_otc.clone().unwrap().as_str()).u128());
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_oracle_pricing() {
+    let mut def = Def::new();
+
+    let mut app = startup(&mut def);
+
+    let creator = "creator";
+    let executor = "executor";
+
+    let fee = def.get_native_fee();
+
+    let offer_denom = "luna";
+    let ask_denom = "usdc";
+
+    mint_token(&mut app, &mut def, creator, (offer_denom, TokenType::Native), "1000");
+    mint_token(&mut app, &mut def, creator, (&fee[0].denom, TokenType::Native), &fee[0].amount.to_string());
+    mint_token(&mut app, &mut def, executor, (ask_denom, TokenType::Native), "10000");
+    mint_token(&mut app, &mut def, executor, (&fee[0].denom, TokenType::Native), &fee[0].amount.to_string());
+
+    let offer_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: offer_denom.to_string(), amount: 1000_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    let ask_items = vec![
+        OtcItemRegistration {
+            item_info: OtcItemInfo::Token { denom: ask_denom.to_string(), amount: 1_u128.into() },
+            vesting: None,
+            pricing: Some(ItemPricing::Oracle(OraclePricing { base: offer_denom.to_string(), base_amount: 1000_u128.into(), adjustment: Some(PriceAdjustment::Discount(300)), max_staleness: 60 })),
+        },
+    ];
+
+    // oracle not configured
+
+    run_create_otc(&mut app, &mut def, creator, Some(executor), &offer_items, &ask_items, fee.clone()).unwrap_err();
+
+    let oracle = create_price_oracle(&mut app, &mut def);
+    set_oracle_price(&mut app, &oracle, offer_denom, ask_denom, Decimal::percent(200));
+
+    run_create_otc(&mut app, &mut def, creator, Some(executor), &offer_items, &ask_items, fee.clone()).unwrap();
+
+    // 1000 * 2 * 0.97
+
+    assert_eq!(1940, qy_otc_active_position(&app, &def, 1).unwrap().ask[0].current_amount.unwrap().u128());
+
+    // price is computed at execution time
+
+    set_oracle_price(&mut app, &oracle, offer_denom, ask_denom, Decimal::percent(300));
+    assert_eq!(2910, qy_otc_active_position(&app, &def, 1).unwrap().ask[0].current_amount.unwrap().u128());
+
+    // stale price
+
+    increase_time(&mut app, 61);
+    assert_eq!(None, qy_otc_active_position(&app, &def, 1).unwrap().ask[0].current_amount);
+
+    set_oracle_price(&mut app, &oracle, offer_denom, ask_denom, Decimal::percent(300));
+    run_execute_otc(&mut app, &mut def, executor, 1, fee.clone()).unwrap();
+
+    assert_eq!(2910, qy_balance_native(&app, ask_denom, creator).u128());
+    assert_eq!(1000, qy_balance_native(&app, offer_denom, executor).u128());
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_dutch_pricing_steps_bound() {
+    let mut def = Def::new();
+
+    let mut app = startup(&mut def);
+
+    let creator = "creator";
+
+    let fee = def.get_native_fee();
+
+    mint_token(&mut app, &mut def, creator, ("luna", TokenType::Native), "100");
+    mint_token(&mut app, &mut def, creator, (&fee[0].denom, TokenType::Native), &fee[0].amount.to_string());
+
+    let now = app.block_info().time.seconds();
+
+    let offer_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: "luna".to_string(), amount: 100_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    let ask = |steps: u64| vec![
+        OtcItemRegistration {
+            item_info: OtcItemInfo::Token { denom: "btc".to_string(), amount: 1000_u128.into() },
+            vesting: None,
+            pricing: Some(ItemPricing::Dutch(DutchPricing { floor: 500_u128.into(), start_time: now, end_time: now + 1000, steps: Some(steps) })),
+        },
+    ];
+
+    // more steps than seconds would overflow the step computation
+
+    run_create_otc(&mut app, &mut def, creator, None, &offer_items, &ask(u64::MAX), fee.clone()).unwrap_err();
+    run_create_otc(&mut app, &mut def, creator, None, &offer_items, &ask(1001), fee.clone()).unwrap_err();
+    run_create_otc(&mut app, &mut def, creator, None, &offer_items, &ask(1000), fee.clone()).unwrap();
+
+    increase_time(&mut app, 999);
+
+    assert_eq!(501, qy_otc_active_position(&app, &def, 1).unwrap().ask[0].current_amount.unwrap().u128());
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_oracle_pricing_bounds() {
+    let mut def = Def::new();
+
+    let mut app = startup(&mut def);
+
+    let creator = "creator";
+
+    let fee = def.get_native_fee();
+
+    mint_token(&mut app, &mut def, creator, ("luna", TokenType::Native), "100");
+    mint_token(&mut app, &mut def, creator, (&fee[0].denom, TokenType::Native), &fee[0].amount.to_string());
+
+    let oracle = create_price_oracle(&mut app, &mut def);
+    set_oracle_price(&mut app, &oracle, "luna", "usdc", Decimal::percent(200));
+
+    let offer_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: "luna".to_string(), amount: 100_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    let ask = |adjustment: PriceAdjustment, max_staleness: u64| vec![
+        OtcItemRegistration {
+            item_info: OtcItemInfo::Token { denom: "usdc".to_string(), amount: 1_u128.into() },
+            vesting: None,
+            pricing: Some(ItemPricing::Oracle(OraclePricing { base: "luna".to_string(), base_amount: 100_u128.into(), adjustment: Some(adjustment), max_staleness })),
+        },
+    ];
+
+    // adjustments are bounded to 10000 bps
+
+    run_create_otc(&mut app, &mut def, creator, None, &offer_items, &ask(PriceAdjustment::Premium(10_001), 60), fee.clone()).unwrap_err();
+    run_create_otc(&mut app, &mut def, creator, None, &offer_items, &ask(PriceAdjustment::Premium(u64::MAX), 60), fee.clone()).unwrap_err();
+    run_create_otc(&mut app, &mut def, creator, None, &offer_items, &ask(PriceAdjustment::Discount(10_000), 60), fee.clone()).unwrap_err();
+
+    // the max staleness doesn't overflow
+
+    run_create_otc(&mut app, &mut def, creator, None, &offer_items, &ask(PriceAdjustment::Premium(10_000), u64::MAX), fee.clone()).unwrap();
+
+    // 100 * 2 * 2
+
+    assert_eq!(400, qy_otc_active_position(&app, &def, 1).unwrap().ask[0].current_amount.unwrap().u128());
+}
@@ -0,0 +1,110 @@
+use cosmwasm_std::Coin;
+use otcer_pkg::otcer::{
+    definitions::{AssetAmount, OtcItemInfo, RingTradeStatus},
+    msgs::{OtcItemRegistration, RingLegRegistration, VestingInfoRegistration},
+};
+
+use crate::helper::{
+    create_token, increase_allowance, increase_time, mint_token, qy_balance_cw20,
+    qy_balance_native, qy_balance_nft, qy_ring_trade, qy_solvency, qy_stats, run_create_ring_trade,
+    run_deposit_ring_leg, run_refund_ring_trade, startup, Def, TokenType,
+};
+
+#[test]
+#[rustfmt::skip]
+pub fn test_ring_trade() {
+    let mut def = Def::new();
+
+    let mut app = startup(&mut def);
+
+    let alice = "alice";
+    let bob = "bob";
+    let carol = "carol";
+    let otc = def.addr_otc.clone().unwrap();
+
+    let fee = def.get_native_fee();
+
+    mint_token(&mut app, &mut def, alice, ("luna", TokenType::Native), "100");
+    mint_token(&mut app, &mut def, alice, (&fee[0].denom, TokenType::Native), &(fee[0].amount.u128() * 2).to_string());
+
+    let token = create_token(&mut app, &mut def, "Token", TokenType::Cw20, vec![(bob, "200")]);
+    let nft = create_token(&mut app, &mut def, "Nft", TokenType::Cw721, vec![(carol, "1")]);
+
+    let item = |item_info: OtcItemInfo| OtcItemRegistration { item_info, vesting: None, pricing: None };
+
+    let legs = vec![
+        RingLegRegistration { participant: alice.to_string(), recipient: bob.to_string(), items: vec![item(OtcItemInfo::Token { denom: "luna".to_string(), amount: 100_u128.into() })] },
+        RingLegRegistration { participant: bob.to_string(), recipient: carol.to_string(), items: vec![item(OtcItemInfo::Cw20 { contract: token.clone(), amount: 200_u128.into() })] },
+        RingLegRegistration { participant: carol.to_string(), recipient: alice.to_string(), items: vec![item(OtcItemInfo::Cw721 { contract: nft.clone(), token_id: "1".to_string() })] },
+    ];
+
+    let deadline = app.block_info().time.seconds() + 1_000;
+
+    // recipients must be other participants, vesting is not allowed
+
+    let mut invalid_legs = legs.clone();
+    invalid_legs[2].recipient = "dave".to_string();
+    run_create_ring_trade(&mut app, &mut def, alice, invalid_legs, deadline, fee.clone()).unwrap_err();
+
+    let mut invalid_legs = legs.clone();
+    invalid_legs[0].items[0].vesting = Some(VestingInfoRegistration { cliff: Some(10), vesting: None, tranches: None });
+    run_create_ring_trade(&mut app, &mut def, alice, invalid_legs, deadline, fee.clone()).unwrap_err();
+
+    run_create_ring_trade(&mut app, &mut def, alice, legs.clone()[..1].to_vec(), deadline, fee.clone()).unwrap_err();
+    run_create_ring_trade(&mut app, &mut def, alice, legs.clone(), deadline, fee.clone()).unwrap();
+
+    assert_eq!(vec![AssetAmount { asset: fee[0].denom.clone(), amount: fee[0].amount }], qy_stats(&app, &def).fees);
+
+    // deposits
+
+    run_deposit_ring_leg(&mut app, &mut def, "dave", 1, vec![]).unwrap_err();
+    run_deposit_ring_leg(&mut app, &mut def, alice, 1, vec![Coin::new(100, "luna")]).unwrap();
+    run_deposit_ring_leg(&mut app, &mut def, alice, 1, vec![]).unwrap_err();
+
+    increase_allowance(&mut app, bob, otc.as_str(), &token, TokenType::Cw20, "200");
+    run_deposit_ring_leg(&mut app, &mut def, bob, 1, vec![]).unwrap();
+
+    assert_eq!(RingTradeStatus::Pending, qy_ring_trade(&app, &def, 1).status);
+    assert_eq!(100, qy_balance_native(&app, "luna", otc.as_str()).u128());
+
+    // the last deposit settles every leg
+
+    increase_allowance(&mut app, carol, otc.as_str(), &nft, TokenType::Cw721, "1");
+    run_deposit_ring_leg(&mut app, &mut def, carol, 1, vec![]).unwrap();
+
+    assert_eq!(100, qy_balance_native(&app, "luna", bob).u128());
+    assert_eq!(200, qy_balance_cw20(&app, &token, carol).u128());
+    assert!(qy_balance_nft(&app, &nft, "1", alice));
+    assert!(matches!(qy_ring_trade(&app, &def, 1).status, RingTradeStatus::Settled(..)));
+
+    run_refund_ring_trade(&mut app, &mut def, alice, 1).unwrap_err();
+
+    // not completed before the deadline
+
+    mint_token(&mut app, &mut def, bob, ("luna", TokenType::Native), "50");
+
+    let legs = vec![
+        RingLegRegistration { participant: bob.to_string(), recipient: carol.to_string(), items: vec![item(OtcItemInfo::Token { denom: "luna".to_string(), amount: 50_u128.into() })] },
+        RingLegRegistration { participant: carol.to_string(), recipient: bob.to_string(), items: vec![item(OtcItemInfo::Cw20 { contract: token.clone(), amount: 200_u128.into() })] },
+    ];
+
+    run_create_ring_trade(&mut app, &mut def, alice, legs, deadline, fee.clone()).unwrap();
+    run_deposit_ring_leg(&mut app, &mut def, bob, 2, vec![Coin::new(50, "luna")]).unwrap();
+
+    run_refund_ring_trade(&mut app, &mut def, alice, 2).unwrap_err();
+
+    increase_time(&mut app, 1_000);
+
+    increase_allowance(&mut app, carol, otc.as_str(), &token, TokenType::Cw20, "200");
+    run_deposit_ring_leg(&mut app, &mut def, carol, 2, vec![]).unwrap_err();
+
+    run_refund_ring_trade(&mut app, &mut def, alice, 2).unwrap();
+
+    assert_eq!(150, qy_balance_native(&app, "luna", bob).u128());
+    assert_eq!(200, qy_balance_cw20(&app, &token, carol).u128());
+    assert!(matches!(qy_ring_trade(&app, &def, 2).status, RingTradeStatus::Refunded(..)));
+
+    run_refund_ring_trade(&mut app, &mut def, alice, 2).unwrap_err();
+
+    assert!(qy_solvency(&app, &def).solvent);
+}
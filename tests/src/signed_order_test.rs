@@ -0,0 +1,148 @@
+use bech32::{ToBase32, Variant};
+use cosmwasm_std::{Addr, Binary, Coin};
+use cw_multi_test::{App, AppResponse, Executor};
+use k256::ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey};
+use otcer::response::ContractError;
+use otcer_pkg::otcer::{
+    definitions::{OtcItemInfo, OtcPositionStatus},
+    msgs::{
+        CancelNoncesMsg, ExecuteMsg, ExecuteSignedOrderMsg, OtcItemRegistration, QueryMsg,
+        SignedOrder, SignedOrderPayload,
+    },
+};
+use rhaki_cw_plus::traits::{IntoAddr, IntoBinary};
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+
+use crate::helper::{
+    create_token, increase_allowance, mint_token, qy_balance_cw20, qy_balance_native,
+    qy_otc_active_position, startup, Def, TokenType,
+};
+
+fn pubkey(key: &SigningKey) -> Binary {
+    Binary::from(key.verifying_key().to_encoded_point(true).as_bytes())
+}
+
+fn address(key: &SigningKey) -> String {
+    let hash = Ripemd160::digest(Sha256::digest(pubkey(key).as_slice()));
+    bech32::encode("cosmos", hash.to_base32(), Variant::Bech32).unwrap()
+}
+
+fn sign(chain_id: &str, otc: &Addr, key: &SigningKey, order: &SignedOrder) -> Binary {
+    let payload = SignedOrderPayload {
+        chain_id: chain_id.to_string(),
+        contract: otc.to_string(),
+        order: order.clone(),
+    }
+    .into_binary()
+    .unwrap();
+    let signature: Signature = key
+        .sign_prehash(&Sha256::digest(payload.as_slice()))
+        .unwrap();
+    Binary::from(
+        signature
+            .normalize_s()
+            .unwrap_or(signature)
+            .to_bytes()
+            .to_vec(),
+    )
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_signed_order() {
+    let mut def = Def::new();
+
+    let mut app = startup(&mut def);
+
+    let taker = "taker";
+    let otc = def.addr_otc.clone().unwrap();
+
+    let fee = def.get_native_fee();
+    let chain_id = app.block_info().chain_id;
+
+    let maker_key = SigningKey::from_bytes(&[7_u8; 32].into()).unwrap();
+    let other_key = SigningKey::from_bytes(&[8_u8; 32].into()).unwrap();
+    let maker = address(&maker_key);
+
+    let token = create_token(&mut app, &mut def, "Token", TokenType::Cw20, vec![(&maker, "300")]);
+    increase_allowance(&mut app, &maker, otc.as_str(), &token, TokenType::Cw20, "300");
+
+    mint_token(&mut app, &mut def, taker, ("btc", TokenType::Native), "100");
+    mint_token(&mut app, &mut def, taker, (&fee[0].denom, TokenType::Native), &(fee[0].amount.u128() * 2).to_string());
+
+    let order = |nonce: u64, expiry: u64| SignedOrder {
+        maker: maker.clone(),
+        offer: vec![OtcItemRegistration { item_info: OtcItemInfo::Cw20 { contract: token.clone(), amount: 100_u128.into() }, vesting: None, pricing: None }],
+        ask: vec![OtcItemRegistration { item_info: OtcItemInfo::Token { denom: "btc".to_string(), amount: 50_u128.into() }, vesting: None, pricing: None }],
+        executor: None,
+        expiry,
+        nonce,
+    };
+
+    let expiry = app.block_info().time.seconds() + 100;
+
+    let execute = |app: &mut App, order: SignedOrder, signature: Binary, pubkey: Binary| -> anyhow::Result<AppResponse> {
+        app.execute_contract(
+            taker.into_unchecked_addr(),
+            otc.clone(),
+            &ExecuteMsg::ExecuteSignedOrder(ExecuteSignedOrderMsg { order, signature, pubkey }),
+            &[Coin::new(50, "btc"), fee[0].clone()],
+        )
+    };
+
+    // tampered order and wrong key
+
+    let mut tampered = order(1, expiry);
+    tampered.ask[0].item_info = OtcItemInfo::Token { denom: "btc".to_string(), amount: 1_u128.into() };
+
+    let err = execute(&mut app, tampered, sign(&chain_id, &otc, &maker_key, &order(1, expiry)), pubkey(&maker_key)).unwrap_err();
+    assert_eq!(ContractError::InvalidSignature {}.to_string(), err.root_cause().to_string());
+
+    let err = execute(&mut app, order(1, expiry), sign(&chain_id, &otc, &other_key, &order(1, expiry)), pubkey(&other_key)).unwrap_err();
+    assert_eq!(ContractError::InvalidSignature {}.to_string(), err.root_cause().to_string());
+
+    // execute
+
+    execute(&mut app, order(1, expiry), sign(&chain_id, &otc, &maker_key, &order(1, expiry)), pubkey(&maker_key)).unwrap();
+
+    assert_eq!(100, qy_balance_cw20(&app, &token, taker).u128());
+    assert_eq!(200, qy_balance_cw20(&app, &token, &maker).u128());
+    assert_eq!(50, qy_balance_native(&app, "btc", &maker).u128());
+
+    let position = qy_otc_active_position(&app, &def, 1).unwrap();
+    assert_eq!(maker, position.owner.to_string());
+    assert!(matches!(position.status, OtcPositionStatus::Executed(..)));
+
+    // replay
+
+    let err = execute(&mut app, order(1, expiry), sign(&chain_id, &otc, &maker_key, &order(1, expiry)), pubkey(&maker_key)).unwrap_err();
+    assert_eq!(ContractError::NonceUsed { nonce: 1 }.to_string(), err.root_cause().to_string());
+
+    // cancelled nonce
+
+    app.execute_contract(maker.into_unchecked_addr(), otc.clone(), &ExecuteMsg::CancelNonces(CancelNoncesMsg { nonces: vec![2] }), &[]).unwrap();
+
+    let used: bool = app.wrap().query_wasm_smart(otc.clone(), &QueryMsg::NonceUsed { maker: maker.clone(), nonce: 2 }).unwrap();
+    assert!(used);
+
+    let err = execute(&mut app, order(2, expiry), sign(&chain_id, &otc, &maker_key, &order(2, expiry)), pubkey(&maker_key)).unwrap_err();
+    assert_eq!(ContractError::NonceUsed { nonce: 2 }.to_string(), err.root_cause().to_string());
+
+    // used and cancelled nonces are skipped
+
+    let res = app.execute_contract(maker.into_unchecked_addr(), otc.clone(), &ExecuteMsg::CancelNonces(CancelNoncesMsg { nonces: vec![1, 2, 4] }), &[]).unwrap();
+
+    let attr = |key: &str| res.events.iter().flat_map(|event| event.attributes.iter()).find(|attr| attr.key == key).unwrap().value.clone();
+    assert_eq!("4", attr("nonces"));
+    assert_eq!("1,2", attr("skipped_nonces"));
+
+    let used: bool = app.wrap().query_wasm_smart(otc.clone(), &QueryMsg::NonceUsed { maker: maker.clone(), nonce: 4 }).unwrap();
+    assert!(used);
+
+    // expired
+
+    let now = app.block_info().time.seconds();
+    let err = execute(&mut app, order(3, now), sign(&chain_id, &otc, &maker_key, &order(3, now)), pubkey(&maker_key)).unwrap_err();
+    assert_eq!(ContractError::OrderExpired {}.to_string(), err.root_cause().to_string());
+}
@@ -0,0 +1,132 @@
+use cosmwasm_std::{
+    from_json,
+    testing::{mock_dependencies, mock_env},
+    Coin, Uint128,
+};
+use cw_multi_test::Executor;
+use cw_storage_plus::Map;
+use otcer::contract::query;
+use otcer_pkg::otcer::{
+    definitions::OtcItemInfo,
+    msgs::{
+        CancelOtcMsg, ExecuteMsg, OtcItemRegistration, QueryMsg, SolvencyResponse,
+        VestingInfoRegistration,
+    },
+};
+use rhaki_cw_plus::traits::IntoAddr;
+
+use crate::helper::{
+    create_token, increase_allowance, increase_time, mint_token, qy_solvency, run_claim_otc,
+    run_create_otc, run_execute_otc, startup, Def, TokenType,
+};
+
+#[test]
+#[rustfmt::skip]
+pub fn test_solvency() {
+    let mut def = Def::new();
+
+    let mut app = startup(&mut def);
+
+    let creator = "creator";
+    let executor = "executor";
+    let otc = def.addr_otc.clone().unwrap();
+
+    let fee = def.get_native_fee();
+
+    mint_token(&mut app, &mut def, creator, ("luna", TokenType::Native), "300");
+    mint_token(&mut app, &mut def, creator, (&fee[0].denom, TokenType::Native), &(fee[0].amount.u128() * 2).to_string());
+    mint_token(&mut app, &mut def, executor, ("btc", TokenType::Native), "50");
+    mint_token(&mut app, &mut def, executor, (&fee[0].denom, TokenType::Native), &fee[0].amount.to_string());
+
+    let cw20 = create_token(&mut app, &mut def, "Token", TokenType::Cw20, vec![(creator, "200")]);
+    let nft = create_token(&mut app, &mut def, "Nft", TokenType::Cw721, vec![(creator, "1")]);
+
+    increase_allowance(&mut app, creator, otc.as_str(), &cw20, TokenType::Cw20, "200");
+    increase_allowance(&mut app, creator, otc.as_str(), &nft, TokenType::Cw721, "1");
+
+    let offer_1 = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: "luna".to_string(), amount: 100_u128.into() }, vesting: Some(VestingInfoRegistration { cliff: None, vesting: Some(100), tranches: None }), pricing: None },
+        OtcItemRegistration { item_info: OtcItemInfo::Cw20 { contract: cw20.clone(), amount: 200_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    let offer_2 = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: "luna".to_string(), amount: 200_u128.into() }, vesting: None, pricing: None },
+        OtcItemRegistration { item_info: OtcItemInfo::Cw721 { contract: nft.clone(), token_id: "1".to_string() }, vesting: None, pricing: None },
+    ];
+
+    let ask_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: "btc".to_string(), amount: 50_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    run_create_otc(&mut app, &mut def, creator, Some(executor), &offer_1, &ask_items, fee.clone()).unwrap();
+    run_create_otc(&mut app, &mut def, creator, Some(executor), &offer_2, &ask_items, fee.clone()).unwrap();
+
+    let solvency = qy_solvency(&app, &def);
+
+    assert!(solvency.solvent);
+    assert_eq!(
+        vec![
+            OtcItemInfo::Cw20 { contract: cw20.clone(), amount: 200_u128.into() },
+            OtcItemInfo::Cw721 { contract: nft.clone(), token_id: "1".to_string() },
+            OtcItemInfo::Token { denom: "luna".to_string(), amount: 300_u128.into() },
+        ],
+        solvency.assets.iter().map(|val| val.liability.clone()).collect::<Vec<_>>()
+    );
+
+    // funds not backing the escrow don't count as liabilities
+
+    mint_token(&mut app, &mut def, otc.as_str(), ("luna", TokenType::Native), "10");
+
+    let solvency = qy_solvency(&app, &def);
+    assert_eq!(Uint128::new(310), solvency.assets[2].balance);
+    assert_eq!(Uint128::new(300), solvency.assets[2].liability.get_amount());
+
+    // liabilities follow execution, vesting claims and cancellation
+
+    run_execute_otc(&mut app, &mut def, executor, 1, fee.clone()).unwrap();
+
+    let solvency = qy_solvency(&app, &def);
+    assert_eq!(2, solvency.assets.len());
+    assert_eq!(Uint128::new(300), solvency.assets[1].liability.get_amount());
+
+    increase_time(&mut app, 50);
+    run_claim_otc(&mut app, &mut def, executor, 1).unwrap();
+
+    assert_eq!(Uint128::new(250), qy_solvency(&app, &def).assets[1].liability.get_amount());
+
+    app.execute_contract(creator.into_unchecked_addr(), otc.clone(), &ExecuteMsg::CancelOtc(CancelOtcMsg { id: 2 }), &[]).unwrap();
+
+    let solvency = qy_solvency(&app, &def);
+    assert_eq!(vec![OtcItemInfo::Token { denom: "luna".to_string(), amount: 50_u128.into() }], solvency.assets.iter().map(|val| val.liability.clone()).collect::<Vec<_>>());
+
+    // a balance lower than the liabilities is reported
+
+    app.init_modules(|router, _, storage| router.bank.init_balance(storage, &otc, vec![Coin::new(40, "luna")])).unwrap();
+
+    assert!(!qy_solvency(&app, &def).solvent);
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_solvency_failing_balance_query() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+
+    let escrow: Map<String, OtcItemInfo> = Map::new("escrow");
+
+    let cw20 = OtcItemInfo::Cw20 { contract: "token".into_unchecked_addr(), amount: Uint128::new(100) };
+    let luna = OtcItemInfo::Token { denom: "luna".to_string(), amount: Uint128::new(100) };
+
+    escrow.save(&mut deps.storage, cw20.asset_key(), &cw20).unwrap();
+    escrow.save(&mut deps.storage, luna.asset_key(), &luna).unwrap();
+    deps.querier.update_balance(env.contract.address.clone(), vec![Coin::new(100, "luna")]);
+
+    // the token without a balance query is reported as insolvent, the other assets are still returned
+
+    let solvency: SolvencyResponse = from_json(query(deps.as_ref(), env, QueryMsg::Solvency { limit: None, start_after: None }).unwrap()).unwrap();
+
+    assert!(!solvency.solvent);
+    assert_eq!((cw20, Uint128::zero()), (solvency.assets[0].liability.clone(), solvency.assets[0].balance));
+    assert!(solvency.assets[0].error.is_some());
+    assert_eq!((luna, Uint128::new(100), None), (solvency.assets[1].liability.clone(), solvency.assets[1].balance, solvency.assets[1].error.clone()));
+}
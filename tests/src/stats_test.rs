@@ -0,0 +1,82 @@
+use cosmwasm_std::Uint128;
+use cw_multi_test::Executor;
+use otcer_pkg::otcer::{
+    definitions::{AssetAmount, OtcItemInfo},
+    msgs::{CancelOtcMsg, ExecuteMsg, OtcItemRegistration, StatusCount},
+};
+use rhaki_cw_plus::traits::IntoAddr;
+
+use crate::helper::{
+    increase_time, mint_token, qy_daily_stats, qy_stats, run_create_otc, run_execute_otc, startup,
+    Def, TokenType,
+};
+
+#[test]
+#[rustfmt::skip]
+pub fn test_stats() {
+    let mut def = Def::new();
+
+    let mut app = startup(&mut def);
+
+    let creator = "creator";
+    let executor = "executor";
+    let otc = def.addr_otc.clone().unwrap();
+
+    let fee = def.get_native_fee();
+    let fee_amount = fee[0].amount.u128();
+
+    mint_token(&mut app, &mut def, creator, ("luna", TokenType::Native), "300");
+    mint_token(&mut app, &mut def, creator, (&fee[0].denom, TokenType::Native), &(fee_amount * 3).to_string());
+    mint_token(&mut app, &mut def, executor, ("btc", TokenType::Native), "100");
+    mint_token(&mut app, &mut def, executor, (&fee[0].denom, TokenType::Native), &(fee_amount * 2).to_string());
+
+    let offer_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: "luna".to_string(), amount: 100_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    let ask_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: "btc".to_string(), amount: 50_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    let amount = |asset: &str, amount: u128| AssetAmount { asset: asset.to_string(), amount: Uint128::new(amount) };
+    let count = |status: &str, count: u64| StatusCount { status: status.to_string(), count };
+
+    let day = app.block_info().time.seconds() / 86_400;
+
+    for _ in 0..3 {
+        run_create_otc(&mut app, &mut def, creator, Some(executor), &offer_items, &ask_items, fee.clone()).unwrap();
+    }
+
+    run_execute_otc(&mut app, &mut def, executor, 1, fee.clone()).unwrap();
+    app.execute_contract(creator.into_unchecked_addr(), otc.clone(), &ExecuteMsg::CancelOtc(CancelOtcMsg { id: 2 }), &[]).unwrap();
+
+    // removed positions are not counted
+
+    let stats = qy_stats(&app, &def);
+
+    assert_eq!(vec![count("executed", 1), count("pending", 1)], stats.positions);
+    assert_eq!(vec![amount("btc", 50), amount("luna", 100)], stats.volume);
+    assert_eq!(vec![amount(&fee[0].denom, fee_amount * 4)], stats.fees);
+    assert_eq!(2, stats.traders);
+
+    // activity of the next day goes in a new bucket
+
+    increase_time(&mut app, 86_400);
+    run_execute_otc(&mut app, &mut def, executor, 3, fee.clone()).unwrap();
+
+    let stats = qy_stats(&app, &def);
+
+    assert_eq!(vec![count("executed", 2), count("pending", 0)], stats.positions);
+    assert_eq!(vec![amount("btc", 100), amount("luna", 200)], stats.volume);
+    assert_eq!(2, stats.traders);
+
+    let daily = qy_daily_stats(&app, &def, None);
+
+    assert_eq!(2, daily.len());
+    assert_eq!((day, 3, 1, 2), (daily[0].day, daily[0].created, daily[0].executed, daily[0].new_traders));
+    assert_eq!((day + 1, 0, 1, 0), (daily[1].day, daily[1].created, daily[1].executed, daily[1].new_traders));
+    assert_eq!(vec![amount("luna", 100), amount("btc", 50)], daily[1].volume);
+    assert_eq!(vec![amount(&fee[0].denom, fee_amount)], daily[1].fees);
+
+    assert_eq!(daily[1..], qy_daily_stats(&app, &def, Some(day)));
+}
@@ -0,0 +1,78 @@
+use otcer_pkg::otcer::{
+    definitions::{OtcItemInfo, OtcPositionStatus},
+    msgs::{OtcItemRegistration, VestingInfoRegistration},
+};
+
+use crate::helper::{
+    increase_time, mint_token, qy_balance_native, qy_otc_active_position, qy_solvency,
+    run_accept_unwind, run_claim_otc, run_create_otc, run_execute_otc, run_propose_unwind, startup,
+    Def, TokenType,
+};
+
+#[test]
+#[rustfmt::skip]
+pub fn test_unwind() {
+    let mut def = Def::new();
+
+    let mut app = startup(&mut def);
+
+    let creator = "creator";
+    let executor = "executor";
+
+    let fee = def.get_native_fee();
+
+    mint_token(&mut app, &mut def, creator, ("luna", TokenType::Native), "1000");
+    mint_token(&mut app, &mut def, creator, (&fee[0].denom, TokenType::Native), &fee[0].amount.to_string());
+    mint_token(&mut app, &mut def, executor, ("btc", TokenType::Native), "500");
+    mint_token(&mut app, &mut def, executor, (&fee[0].denom, TokenType::Native), &fee[0].amount.to_string());
+
+    let vesting = Some(VestingInfoRegistration { cliff: None, vesting: Some(1_000), tranches: None });
+
+    let offer_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: "luna".to_string(), amount: 1_000_u128.into() }, vesting: vesting.clone(), pricing: None },
+    ];
+
+    let ask_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Token { denom: "btc".to_string(), amount: 500_u128.into() }, vesting, pricing: None },
+    ];
+
+    run_create_otc(&mut app, &mut def, creator, Some(executor), &offer_items, &ask_items, fee.clone()).unwrap();
+
+    // only positions in vesting can be unwound
+
+    run_propose_unwind(&mut app, &mut def, creator, 1).unwrap_err();
+
+    run_execute_otc(&mut app, &mut def, executor, 1, fee.clone()).unwrap();
+
+    increase_time(&mut app, 400);
+    run_claim_otc(&mut app, &mut def, creator, 1).unwrap();
+
+    assert_eq!(200, qy_balance_native(&app, "btc", creator).u128());
+
+    // proposed by one side, accepted by the other
+
+    run_accept_unwind(&mut app, &mut def, creator, 1).unwrap_err();
+    run_propose_unwind(&mut app, &mut def, "stranger", 1).unwrap_err();
+    run_propose_unwind(&mut app, &mut def, executor, 1).unwrap();
+
+    run_accept_unwind(&mut app, &mut def, executor, 1).unwrap_err();
+    run_accept_unwind(&mut app, &mut def, "stranger", 1).unwrap_err();
+    run_accept_unwind(&mut app, &mut def, creator, 1).unwrap();
+
+    // unclaimed amounts go back to the depositors, claimed amounts are kept
+
+    assert_eq!(1_000, qy_balance_native(&app, "luna", creator).u128());
+    assert_eq!(0, qy_balance_native(&app, "luna", executor).u128());
+    assert_eq!(200, qy_balance_native(&app, "btc", creator).u128());
+    assert_eq!(300, qy_balance_native(&app, "btc", executor).u128());
+
+    assert!(matches!(qy_otc_active_position(&app, &def, 1).unwrap().status, OtcPositionStatus::Unwound(..)));
+
+    increase_time(&mut app, 1_000);
+
+    run_claim_otc(&mut app, &mut def, creator, 1).unwrap_err();
+    run_claim_otc(&mut app, &mut def, executor, 1).unwrap_err();
+    run_accept_unwind(&mut app, &mut def, creator, 1).unwrap_err();
+
+    assert!(qy_solvency(&app, &def).solvent);
+}
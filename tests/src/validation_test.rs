@@ -0,0 +1,116 @@
+use cosmwasm_std::Coin;
+use cw_multi_test::Executor;
+use otcer::response::ContractError;
+use otcer_pkg::otcer::{
+    definitions::{OtcItemInfo, TokenMetadata},
+    msgs::{CreateOtcMsg, ExecuteMsg, OtcItemRegistration, VestingInfoRegistration},
+};
+use rhaki_cw_plus::traits::IntoAddr;
+
+use crate::helper::{
+    create_token, increase_allowance, mint_token, qy_otc_active_position, run_create_otc, startup,
+    Def, TokenType,
+};
+
+#[test]
+#[rustfmt::skip]
+pub fn test_position_validation() {
+    let mut def = Def::new();
+
+    let mut app = startup(&mut def);
+
+    let creator = "creator";
+    let executor = "executor";
+    let otc = def.addr_otc.clone().unwrap();
+
+    let fee = def.get_native_fee();
+    let max_items = def.max_items_per_side;
+
+    mint_token(&mut app, &mut def, creator, ("luna", TokenType::Native), "1000");
+    mint_token(&mut app, &mut def, creator, (&fee[0].denom, TokenType::Native), &(fee[0].amount.u128() * 10).to_string());
+
+    let nft = create_token(&mut app, &mut def, "Nft", TokenType::Cw721, vec![(creator, "1")]);
+    increase_allowance(&mut app, creator, otc.as_str(), &nft, TokenType::Cw721, "1");
+
+    let token = |denom: &str, amount: u128| OtcItemRegistration { item_info: OtcItemInfo::Token { denom: denom.to_string(), amount: amount.into() }, vesting: None, pricing: None };
+    let nft_item = OtcItemRegistration { item_info: OtcItemInfo::Cw721 { contract: nft.clone(), token_id: "1".to_string() }, vesting: None, pricing: None };
+
+    let assert_err = |app: &mut cw_multi_test::App, def: &mut Def, executor: Option<&str>, offer: Vec<OtcItemRegistration>, ask: Vec<OtcItemRegistration>, expected: ContractError| {
+        let err = run_create_otc(app, def, creator, executor, &offer, &ask, fee.clone()).unwrap_err();
+        assert_eq!(expected.to_string(), err.root_cause().to_string());
+    };
+
+    assert_err(&mut app, &mut def, None, vec![], vec![token("btc", 10)], ContractError::EmptyItems { side: "offer".to_string() });
+    assert_err(&mut app, &mut def, None, vec![token("luna", 10)], vec![], ContractError::EmptyItems { side: "ask".to_string() });
+    assert_err(&mut app, &mut def, None, vec![token("luna", 10)], vec![token("btc", 0)], ContractError::ZeroAmount { asset: "native:btc".to_string() });
+    assert_err(&mut app, &mut def, Some(creator), vec![token("luna", 10)], vec![token("btc", 10)], ContractError::ExecutorIsOwner {});
+    assert_err(&mut app, &mut def, None, vec![nft_item.clone(), nft_item.clone()], vec![token("btc", 10)], ContractError::DuplicateAsset { asset: format!("cw721:{nft}:1") });
+    assert_err(&mut app, &mut def, None, vec![nft_item.clone()], vec![nft_item.clone()], ContractError::DuplicateAsset { asset: format!("cw721:{nft}:1") });
+    assert_err(&mut app, &mut def, None, vec![token("luna", 10)], (0..=max_items).map(|i| token(&format!("denom{i}"), 10)).collect(), ContractError::TooManyItems { side: "ask".to_string(), max: max_items });
+
+    // same denom with a different vesting can't be merged
+
+    let mut vested = token("luna", 10);
+    vested.vesting = Some(VestingInfoRegistration { cliff: Some(10), vesting: None, tranches: None });
+
+    assert_err(&mut app, &mut def, None, vec![token("luna", 10), vested], vec![token("btc", 10)], ContractError::DuplicateAsset { asset: "native:luna".to_string() });
+
+    // fungible items of the same asset are merged
+
+    app.execute_contract(
+        creator.into_unchecked_addr(),
+        otc.clone(),
+        &ExecuteMsg::CreateOtc(CreateOtcMsg { executor: Some(executor.to_string()), offer: vec![token("luna", 40), token("luna", 60)], ask: vec![token("btc", 10), token("btc", 5)], auction: None, hook: None, referrer: None, hash_lock: None, arbiter: None, milestones: None }),
+        &[Coin::new(100, "luna"), fee[0].clone()],
+    ).unwrap();
+
+    let position = qy_otc_active_position(&app, &def, 1).unwrap();
+
+    assert_eq!(vec![token("luna", 100).item_info], position.offer.iter().map(|val| val.item_info.clone()).collect::<Vec<_>>());
+    assert_eq!(vec![token("btc", 15).item_info], position.ask.iter().map(|val| val.item_info.clone()).collect::<Vec<_>>());
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_token_contracts_verification() {
+    let mut def = Def::new();
+
+    let mut app = startup(&mut def);
+
+    let creator = "creator";
+    let otc = def.addr_otc.clone().unwrap();
+
+    let fee = def.get_native_fee();
+
+    mint_token(&mut app, &mut def, creator, (&fee[0].denom, TokenType::Native), &(fee[0].amount.u128() * 3).to_string());
+
+    let cw20 = create_token(&mut app, &mut def, "Token", TokenType::Cw20, vec![(creator, "100")]);
+    let nft = create_token(&mut app, &mut def, "Nft", TokenType::Cw721, vec![(creator, "1")]);
+    increase_allowance(&mut app, creator, otc.as_str(), &cw20, TokenType::Cw20, "100");
+
+    let offer_items = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Cw20 { contract: cw20.clone(), amount: 100_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    let ask = |token_id: &str| vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Cw721 { contract: nft.clone(), token_id: token_id.to_string() }, vesting: None, pricing: None },
+    ];
+
+    // a contract that is not a cw20
+
+    let fake_offer = vec![
+        OtcItemRegistration { item_info: OtcItemInfo::Cw20 { contract: nft.clone(), amount: 100_u128.into() }, vesting: None, pricing: None },
+    ];
+
+    run_create_otc(&mut app, &mut def, creator, None, &fake_offer, &ask("1"), fee.clone()).unwrap_err();
+
+    // the ask nft must exist
+
+    run_create_otc(&mut app, &mut def, creator, None, &offer_items, &ask("2"), fee.clone()).unwrap_err();
+    run_create_otc(&mut app, &mut def, creator, None, &offer_items, &ask("1"), fee.clone()).unwrap();
+
+    let position = qy_otc_active_position(&app, &def, 1).unwrap();
+
+    assert_eq!(Some(TokenMetadata { name: "Token".to_string(), symbol: "Token".to_string(), decimals: Some(6) }), position.offer[0].metadata);
+    assert_eq!(Some(TokenMetadata { name: "Nft".to_string(), symbol: "Nft".to_string(), decimals: None }), position.ask[0].metadata);
+}
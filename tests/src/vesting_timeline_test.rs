@@ -0,0 +1,110 @@
+use cosmwasm_std::{Coin, Decimal, Uint128};
+use cw_multi_test::Executor;
+use otcer_pkg::otcer::{
+    definitions::{OtcItemInfo, Tranche, VestingEvent, VestingEventKind},
+    msgs::{
+        ApproveMilestoneMsg, CreateOtcMsg, ExecuteMsg, MilestonePlanRegistration,
+        OtcItemRegistration, VestingInfoRegistration,
+    },
+};
+use rhaki_cw_plus::traits::IntoAddr;
+
+use crate::helper::{
+    increase_time, mint_token, qy_claimable, qy_vesting_timeline, run_execute_otc, startup, Def,
+    TokenType,
+};
+
+#[test]
+#[rustfmt::skip]
+pub fn test_vesting_timeline() {
+    let mut def = Def::new();
+
+    let mut app = startup(&mut def);
+
+    let creator = "creator";
+    let executor = "executor";
+    let approver = "approver";
+    let otc = def.addr_otc.clone().unwrap();
+
+    let fee = def.get_native_fee();
+
+    mint_token(&mut app, &mut def, creator, ("luna", TokenType::Native), "1000");
+    mint_token(&mut app, &mut def, creator, ("atom", TokenType::Native), "500");
+    mint_token(&mut app, &mut def, creator, (&fee[0].denom, TokenType::Native), &fee[0].amount.to_string());
+    mint_token(&mut app, &mut def, executor, ("btc", TokenType::Native), "100");
+    mint_token(&mut app, &mut def, executor, (&fee[0].denom, TokenType::Native), &fee[0].amount.to_string());
+
+    let item = |denom: &str, amount: u128, vesting: Option<VestingInfoRegistration>| OtcItemRegistration { item_info: OtcItemInfo::Token { denom: denom.to_string(), amount: amount.into() }, vesting, pricing: None };
+
+    let tranches = vec![Tranche { milestone: 0, share: Decimal::percent(40) }, Tranche { milestone: 1, share: Decimal::percent(60) }];
+
+    let offer = vec![
+        item("luna", 1_000, Some(VestingInfoRegistration { cliff: Some(100), vesting: Some(1_000), tranches: Some(tranches) })),
+        item("atom", 500, Some(VestingInfoRegistration { cliff: Some(200), vesting: None, tranches: None })),
+    ];
+
+    let mut coins = vec![Coin::new(1_000, "luna"), Coin::new(500, "atom")];
+    coins.extend(fee.clone());
+
+    app.execute_contract(creator.into_unchecked_addr(), otc.clone(), &ExecuteMsg::CreateOtc(CreateOtcMsg {
+        executor: Some(executor.to_string()),
+        offer,
+        ask: vec![item("btc", 100, None)],
+        auction: None,
+        hook: None,
+        referrer: None,
+        hash_lock: None,
+        arbiter: None,
+        milestones: Some(MilestonePlanRegistration { approver: Some(approver.to_string()), milestones: vec!["mainnet".to_string(), "listing".to_string()] }),
+    }), &coins).unwrap();
+
+    // only vesting positions have a timeline
+
+    let timeline = qy_vesting_timeline(&app, &def, 1).unwrap();
+    assert!(timeline.offer.is_empty() && timeline.ask.is_empty());
+
+    run_execute_otc(&mut app, &mut def, executor, 1, fee.clone()).unwrap();
+
+    let start = app.block_info().time.seconds();
+
+    let event = |time: u64, unlocked: u128, kind: VestingEventKind| VestingEvent { time, unlocked: Uint128::new(unlocked), kind };
+
+    let timeline = qy_vesting_timeline(&app, &def, 1).unwrap();
+
+    // nothing unlocks until a milestone is completed
+
+    assert_eq!(vec![event(start, 0, VestingEventKind::Start), event(start + 100, 0, VestingEventKind::Cliff)], timeline.offer[0].events);
+    assert_eq!(1_000, timeline.offer[0].awaiting_milestones.u128());
+
+    // a cliff without vesting unlocks everything once passed
+
+    assert_eq!(vec![event(start, 0, VestingEventKind::Start), event(start + 201, 500, VestingEventKind::Cliff)], timeline.offer[1].events);
+
+    // items without vesting are sent at the start
+
+    assert_eq!(vec![event(start, 100, VestingEventKind::Start)], timeline.ask[0].events);
+    assert_eq!(100, timeline.ask[0].claimed.u128());
+
+    // the tranche unlocks the vested share at once, then follows the vesting
+
+    increase_time(&mut app, 600);
+    app.execute_contract(approver.into_unchecked_addr(), otc.clone(), &ExecuteMsg::ApproveMilestone(ApproveMilestoneMsg { id: 1, milestone: 0 }), &[]).unwrap();
+
+    let timeline = qy_vesting_timeline(&app, &def, 1).unwrap();
+
+    let expected = vec![
+        event(start, 0, VestingEventKind::Start),
+        event(start + 100, 0, VestingEventKind::Cliff),
+        event(start + 600, 200, VestingEventKind::Milestone),
+        event(start + 1_100, 400, VestingEventKind::Linear),
+    ];
+
+    assert_eq!(expected, timeline.offer[0].events);
+    assert_eq!(600, timeline.offer[0].awaiting_milestones.u128());
+
+    // the timeline matches the claimable amounts
+
+    for (at_time, unlocked) in [(start + 600, 200), (start + 850, 300), (start + 1_100, 400)] {
+        assert_eq!(unlocked, qy_claimable(&app, &def, 1, executor, Some(at_time)).unwrap()[0].claimable.u128());
+    }
+}